@@ -0,0 +1,18 @@
+-- Balances de carga docente de cada usuario
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/001_balances.sql
+
+BEGIN;
+
+CREATE TABLE balances (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES usuarios(id) ON DELETE CASCADE,
+    academic_year VARCHAR(10) NOT NULL,
+    period VARCHAR(10) NOT NULL,
+    school_year VARCHAR(20) NOT NULL,
+    start_date DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMIT;
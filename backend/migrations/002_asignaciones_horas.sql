@@ -0,0 +1,23 @@
+-- Horas por semana y asignatura de un balance.
+-- Semanas: 1-15 lectivas, 16 consultas, 17 exámenes finales, 18 extra I, 19 extra II
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/002_asignaciones_horas.sql
+
+BEGIN;
+
+CREATE TABLE asignaciones_horas (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE RESTRICT,
+    week INTEGER NOT NULL CHECK (week BETWEEN 1 AND 19),
+    c INTEGER NOT NULL DEFAULT 0,
+    cp INTEGER NOT NULL DEFAULT 0,
+    s INTEGER NOT NULL DEFAULT 0,
+    pl INTEGER NOT NULL DEFAULT 0,
+    te INTEGER NOT NULL DEFAULT 0,
+    t INTEGER NOT NULL DEFAULT 0,
+    pp INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (balance_id, asignatura_id, week)
+);
+
+COMMIT;
@@ -0,0 +1,16 @@
+-- Feriados, vacaciones y eventos institucionales (p. ej. preparación militar)
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/003_dias_no_lectivos.sql
+
+BEGIN;
+
+CREATE TABLE dias_no_lectivos (
+    id SERIAL PRIMARY KEY,
+    description VARCHAR(255) NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('feriado', 'vacaciones', 'evento')),
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL CHECK (end_date >= start_date),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMIT;
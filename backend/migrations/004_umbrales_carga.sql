@@ -0,0 +1,13 @@
+-- Umbrales de validación de carga por año académico
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/004_umbrales_carga.sql
+
+BEGIN;
+
+CREATE TABLE umbrales_carga (
+    academic_year VARCHAR(10) PRIMARY KEY,
+    max_weekly_hours INTEGER NOT NULL CHECK (max_weekly_hours > 0),
+    max_weekly_pp INTEGER NOT NULL CHECK (max_weekly_pp >= 0)
+);
+
+COMMIT;
@@ -0,0 +1,17 @@
+-- Revisiones inmutables de un balance (una por cada guardado)
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/005_balance_revisiones.sql
+
+BEGIN;
+
+CREATE TABLE balance_revisiones (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES usuarios(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    snapshot JSONB NOT NULL,
+    UNIQUE (balance_id, revision)
+);
+
+COMMIT;
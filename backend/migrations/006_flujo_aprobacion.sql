@@ -0,0 +1,26 @@
+-- Flujo de aprobación de balances: aprobadores (Jefe de Colectivo de
+-- Carrera), estado del balance e historial de transiciones. Los balances
+-- existentes quedan en borrador.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/006_flujo_aprobacion.sql
+
+BEGIN;
+
+ALTER TABLE usuarios
+    ADD COLUMN is_approver BOOLEAN DEFAULT FALSE;
+
+ALTER TABLE balances
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'borrador'
+        CHECK (status IN ('borrador', 'enviado', 'aprobado', 'rechazado'));
+
+CREATE TABLE balance_transiciones (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    from_status VARCHAR(20) NOT NULL,
+    to_status VARCHAR(20) NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES usuarios(id),
+    comment TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMIT;
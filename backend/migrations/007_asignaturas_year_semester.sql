@@ -0,0 +1,52 @@
+-- Convierte asignaturas.year y asignaturas.semester de texto libre a tipos
+-- enumerados. Normaliza los valores existentes ("1ro.ICS", "1", "Primero",
+-- "I", ...) y aborta si queda alguna fila que no se pueda interpretar.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/007_asignaturas_year_semester.sql
+
+BEGIN;
+
+CREATE TYPE academic_year AS ENUM ('1ro', '2do', '3ro', '4to');
+CREATE TYPE semester AS ENUM ('1ero', '2do');
+
+UPDATE asignaturas
+SET year = CASE lower(trim(split_part(year, '.', 1)))
+        WHEN '1ro' THEN '1ro' WHEN '1' THEN '1ro' WHEN '1er' THEN '1ro'
+        WHEN '1ero' THEN '1ro' WHEN 'primero' THEN '1ro' WHEN 'primer' THEN '1ro'
+        WHEN '2do' THEN '2do' WHEN '2' THEN '2do' WHEN 'segundo' THEN '2do'
+        WHEN '3ro' THEN '3ro' WHEN '3' THEN '3ro' WHEN '3er' THEN '3ro'
+        WHEN '3ero' THEN '3ro' WHEN 'tercero' THEN '3ro' WHEN 'tercer' THEN '3ro'
+        WHEN '4to' THEN '4to' WHEN '4' THEN '4to' WHEN 'cuarto' THEN '4to'
+        ELSE year
+    END,
+    semester = CASE lower(trim(semester))
+        WHEN '1ero' THEN '1ero' WHEN '1ro' THEN '1ero' WHEN '1er' THEN '1ero'
+        WHEN '1' THEN '1ero' WHEN 'primero' THEN '1ero' WHEN 'primer' THEN '1ero'
+        WHEN 'i' THEN '1ero'
+        WHEN '2do' THEN '2do' WHEN '2' THEN '2do' WHEN 'segundo' THEN '2do'
+        WHEN 'ii' THEN '2do'
+        ELSE semester
+    END;
+
+DO $$
+DECLARE
+    invalid TEXT;
+BEGIN
+    SELECT string_agg(format('%s (%s, %s)', id, year, semester), '; ')
+    INTO invalid
+    FROM asignaturas
+    WHERE year NOT IN ('1ro', '2do', '3ro', '4to')
+       OR semester NOT IN ('1ero', '2do');
+
+    IF invalid IS NOT NULL THEN
+        RAISE EXCEPTION 'Asignaturas con año o semestre no reconocido: %', invalid;
+    END IF;
+END $$;
+
+ALTER TABLE asignaturas
+    ALTER COLUMN year TYPE academic_year USING year::academic_year,
+    ALTER COLUMN semester TYPE semester USING semester::semester;
+
+CREATE INDEX asignaturas_year_semester_idx ON asignaturas (year, semester);
+
+COMMIT;
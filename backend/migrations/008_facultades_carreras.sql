@@ -0,0 +1,47 @@
+-- Jerarquía facultad / carrera / plan de estudio. Las asignaturas y los
+-- balances existentes se asignan a la carrera de Ingeniería en
+-- Ciberseguridad (ICS), que era la única contemplada hasta ahora.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/008_facultades_carreras.sql
+
+BEGIN;
+
+CREATE TABLE facultades (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(150) UNIQUE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE carreras (
+    id SERIAL PRIMARY KEY,
+    facultad_id INTEGER NOT NULL REFERENCES facultades(id) ON DELETE RESTRICT,
+    name VARCHAR(150) NOT NULL,
+    code VARCHAR(10) UNIQUE NOT NULL,
+    years INTEGER NOT NULL DEFAULT 4 CHECK (years BETWEEN 1 AND 4),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE planes_de_estudio (
+    id SERIAL PRIMARY KEY,
+    carrera_id INTEGER NOT NULL REFERENCES carreras(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    school_year VARCHAR(20) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (carrera_id, name)
+);
+
+INSERT INTO facultades (name) VALUES ('Facultad de Ciberseguridad');
+INSERT INTO carreras (facultad_id, name, code, years)
+SELECT id, 'Ingeniería en Ciberseguridad', 'ICS', 4
+FROM facultades WHERE name = 'Facultad de Ciberseguridad';
+
+ALTER TABLE asignaturas
+    ADD COLUMN carrera_id INTEGER REFERENCES carreras(id) ON DELETE RESTRICT;
+ALTER TABLE balances
+    ADD COLUMN carrera_id INTEGER REFERENCES carreras(id) ON DELETE RESTRICT;
+
+UPDATE asignaturas SET carrera_id = (SELECT id FROM carreras WHERE code = 'ICS');
+UPDATE balances SET carrera_id = (SELECT id FROM carreras WHERE code = 'ICS');
+
+COMMIT;
@@ -0,0 +1,26 @@
+-- Profesores y asignación de cada tipo de actividad de una asignatura a un
+-- profesor y grupo
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/009_profesores.sql
+
+BEGIN;
+
+CREATE TABLE profesores (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    email VARCHAR(150),
+    usuario_id INTEGER UNIQUE REFERENCES usuarios(id) ON DELETE SET NULL,
+    max_weekly_hours INTEGER NOT NULL DEFAULT 16 CHECK (max_weekly_hours > 0),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE asignaciones_profesores (
+    id SERIAL PRIMARY KEY,
+    profesor_id INTEGER NOT NULL REFERENCES profesores(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE CASCADE,
+    activity VARCHAR(2) NOT NULL CHECK (activity IN ('C', 'CP', 'S', 'PL', 'TE', 'T', 'PP')),
+    group_name VARCHAR(20) NOT NULL,
+    UNIQUE (asignatura_id, activity, group_name)
+);
+
+COMMIT;
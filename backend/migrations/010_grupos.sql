@@ -0,0 +1,26 @@
+-- Grupos de estudiantes de cada año académico de una carrera. Las prácticas
+-- de laboratorio (PL) se imparten por subgrupos. Un balance puede tener sus
+-- propios grupos, que sustituyen a los del año.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/010_grupos.sql
+
+BEGIN;
+
+CREATE TABLE grupos_anio (
+    id SERIAL PRIMARY KEY,
+    carrera_id INTEGER NOT NULL REFERENCES carreras(id) ON DELETE CASCADE,
+    academic_year VARCHAR(10) NOT NULL,
+    name VARCHAR(20) NOT NULL,
+    subgroups INTEGER NOT NULL DEFAULT 1 CHECK (subgroups BETWEEN 1 AND 10),
+    UNIQUE (carrera_id, academic_year, name)
+);
+
+CREATE TABLE grupos (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    name VARCHAR(20) NOT NULL,
+    subgroups INTEGER NOT NULL DEFAULT 1 CHECK (subgroups BETWEEN 1 AND 10),
+    UNIQUE (balance_id, name)
+);
+
+COMMIT;
@@ -0,0 +1,21 @@
+-- Horas oficiales del plan de estudio por asignatura y tipo de actividad
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/011_horas_plan.sql
+
+BEGIN;
+
+CREATE TABLE horas_plan (
+    id SERIAL PRIMARY KEY,
+    plan_id INTEGER NOT NULL REFERENCES planes_de_estudio(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE CASCADE,
+    c INTEGER NOT NULL DEFAULT 0,
+    cp INTEGER NOT NULL DEFAULT 0,
+    s INTEGER NOT NULL DEFAULT 0,
+    pl INTEGER NOT NULL DEFAULT 0,
+    te INTEGER NOT NULL DEFAULT 0,
+    t INTEGER NOT NULL DEFAULT 0,
+    pp INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (plan_id, asignatura_id)
+);
+
+COMMIT;
@@ -0,0 +1,15 @@
+-- Fechas exactas de las pruebas parciales (PP) de un balance
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/012_fechas_evaluacion.sql
+
+BEGIN;
+
+CREATE TABLE fechas_evaluacion (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    UNIQUE (balance_id, asignatura_id, date)
+);
+
+COMMIT;
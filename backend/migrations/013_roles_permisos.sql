@@ -0,0 +1,66 @@
+-- Roles y permisos granulares. El rol es la única fuente de permisos: los
+-- usuarios existentes reciben el rol equivalente a sus indicadores antiguos
+-- (isadmin, can_write, is_approver), que dejan de conceder permisos.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/013_roles_permisos.sql
+
+BEGIN;
+
+CREATE TABLE roles (
+    id SERIAL PRIMARY KEY,
+    code VARCHAR(30) UNIQUE NOT NULL,
+    name VARCHAR(100) NOT NULL
+);
+
+CREATE TABLE permisos (
+    code VARCHAR(50) PRIMARY KEY,
+    description VARCHAR(200) NOT NULL
+);
+
+CREATE TABLE roles_permisos (
+    rol_id INTEGER NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+    permiso_code VARCHAR(50) NOT NULL REFERENCES permisos(code) ON DELETE CASCADE,
+    PRIMARY KEY (rol_id, permiso_code)
+);
+
+INSERT INTO permisos (code, description) VALUES
+    ('balances.view_all', 'Consultar los balances de todos los usuarios'),
+    ('balances.edit_all', 'Modificar los balances de otros usuarios'),
+    ('balances.approve', 'Aprobar, rechazar y reabrir balances'),
+    ('catalog.write', 'Modificar el catálogo de asignaturas y las horas de los planes'),
+    ('academic.manage', 'Administrar facultades, carreras y planes de estudio'),
+    ('professors.manage', 'Administrar profesores y sus asignaciones'),
+    ('professors.view_load', 'Consultar la carga de todos los profesores'),
+    ('settings.manage', 'Administrar umbrales de carga y días no lectivos'),
+    ('users.manage', 'Administrar usuarios, roles y permisos');
+
+INSERT INTO roles (code, name) VALUES
+    ('viewer', 'Consulta'),
+    ('coordinator', 'Coordinador de año'),
+    ('department_head', 'Jefe de Colectivo de Carrera'),
+    ('admin', 'Administrador');
+
+INSERT INTO roles_permisos (rol_id, permiso_code)
+SELECT r.id, p.code
+FROM roles r
+JOIN permisos p ON
+    (r.code = 'viewer' AND p.code IN ('balances.view_all', 'professors.view_load'))
+    OR (r.code = 'coordinator' AND p.code IN ('catalog.write'))
+    OR (r.code = 'department_head' AND p.code IN (
+        'balances.view_all', 'balances.approve', 'catalog.write', 'professors.view_load'))
+    OR r.code = 'admin';
+
+ALTER TABLE usuarios ADD COLUMN rol_id INTEGER REFERENCES roles(id) ON DELETE SET NULL;
+
+-- isadmin -> admin, is_approver -> department_head (que incluye además
+-- catalog.write), can_write -> coordinator; sin indicadores, sin rol
+UPDATE usuarios u
+SET rol_id = r.id
+FROM roles r
+WHERE r.code = CASE
+    WHEN u.isadmin THEN 'admin'
+    WHEN u.is_approver THEN 'department_head'
+    WHEN u.can_write THEN 'coordinator'
+END;
+
+COMMIT;
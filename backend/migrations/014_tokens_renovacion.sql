@@ -0,0 +1,22 @@
+-- Tokens de renovación (refresh). Solo se guarda el hash SHA-256; cada uso
+-- rota el token dentro de la misma familia y reutilizar uno ya usado revoca
+-- la familia completa.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/014_tokens_renovacion.sql
+
+BEGIN;
+
+CREATE TABLE tokens_renovacion (
+    id SERIAL PRIMARY KEY,
+    usuario_id INTEGER NOT NULL REFERENCES usuarios(id) ON DELETE CASCADE,
+    family VARCHAR(64) NOT NULL,
+    token_hash CHAR(64) UNIQUE NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX idx_tokens_renovacion_family ON tokens_renovacion (family);
+
+COMMIT;
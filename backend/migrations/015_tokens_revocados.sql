@@ -0,0 +1,15 @@
+-- Tokens de acceso revocados antes de caducar (logout). Se identifican por
+-- el claim `jti` y se pueden borrar una vez pasada su expiración.
+--
+-- Uso: psql "$DATABASE_URL" -f migrations/015_tokens_revocados.sql
+
+BEGIN;
+
+CREATE TABLE tokens_revocados (
+    jti VARCHAR(64) PRIMARY KEY,
+    usuario_id INTEGER NOT NULL REFERENCES usuarios(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMIT;
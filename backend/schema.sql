@@ -1,3 +1,49 @@
+-- Roles y permisos. Los permisos viajan en los claims del JWT y las rutas
+-- declaran el que necesitan con el guardián `Require<P>`.
+CREATE TABLE roles (
+    id SERIAL PRIMARY KEY,
+    code VARCHAR(30) UNIQUE NOT NULL,
+    name VARCHAR(100) NOT NULL
+);
+
+CREATE TABLE permisos (
+    code VARCHAR(50) PRIMARY KEY,
+    description VARCHAR(200) NOT NULL
+);
+
+CREATE TABLE roles_permisos (
+    rol_id INTEGER NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+    permiso_code VARCHAR(50) NOT NULL REFERENCES permisos(code) ON DELETE CASCADE,
+    PRIMARY KEY (rol_id, permiso_code)
+);
+
+INSERT INTO permisos (code, description) VALUES
+    ('balances.view_all', 'Consultar los balances de todos los usuarios'),
+    ('balances.edit_all', 'Modificar los balances de otros usuarios'),
+    ('balances.approve', 'Aprobar, rechazar y reabrir balances'),
+    ('catalog.write', 'Modificar el catálogo de asignaturas y las horas de los planes'),
+    ('academic.manage', 'Administrar facultades, carreras y planes de estudio'),
+    ('professors.manage', 'Administrar profesores y sus asignaciones'),
+    ('professors.view_load', 'Consultar la carga de todos los profesores'),
+    ('settings.manage', 'Administrar umbrales de carga y días no lectivos'),
+    ('users.manage', 'Administrar usuarios, roles y permisos');
+
+INSERT INTO roles (code, name) VALUES
+    ('viewer', 'Consulta'),
+    ('coordinator', 'Coordinador de año'),
+    ('department_head', 'Jefe de Colectivo de Carrera'),
+    ('admin', 'Administrador');
+
+INSERT INTO roles_permisos (rol_id, permiso_code)
+SELECT r.id, p.code
+FROM roles r
+JOIN permisos p ON
+    (r.code = 'viewer' AND p.code IN ('balances.view_all', 'professors.view_load'))
+    OR (r.code = 'coordinator' AND p.code IN ('catalog.write'))
+    OR (r.code = 'department_head' AND p.code IN (
+        'balances.view_all', 'balances.approve', 'catalog.write', 'professors.view_load'))
+    OR r.code = 'admin';
+
 CREATE TABLE usuarios (
     id SERIAL PRIMARY KEY,
     name VARCHAR(100) NOT NULL,
@@ -5,12 +51,210 @@ CREATE TABLE usuarios (
     token VARCHAR(255) NOT NULL,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     is_admin BOOLEAN DEFAULT FALSE,
-    can_write BOOLEAN DEFAULT FALSE
+    can_write BOOLEAN DEFAULT FALSE,
+    is_approver BOOLEAN DEFAULT FALSE,
+    rol_id INTEGER REFERENCES roles(id) ON DELETE SET NULL
+);
+
+-- Facultades, carreras y planes de estudio
+CREATE TABLE facultades (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(150) UNIQUE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE carreras (
+    id SERIAL PRIMARY KEY,
+    facultad_id INTEGER NOT NULL REFERENCES facultades(id) ON DELETE RESTRICT,
+    name VARCHAR(150) NOT NULL,
+    code VARCHAR(10) UNIQUE NOT NULL,
+    years INTEGER NOT NULL DEFAULT 4 CHECK (years BETWEEN 1 AND 4),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE planes_de_estudio (
+    id SERIAL PRIMARY KEY,
+    carrera_id INTEGER NOT NULL REFERENCES carreras(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    school_year VARCHAR(20) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (carrera_id, name)
 );
 
+INSERT INTO facultades (name) VALUES ('Facultad de Ciberseguridad');
+INSERT INTO carreras (facultad_id, name, code, years)
+VALUES (1, 'Ingeniería en Ciberseguridad', 'ICS', 4);
+
+-- Años académicos (1ro.ICS - 4to.ICS) y semestres admitidos
+CREATE TYPE academic_year AS ENUM ('1ro', '2do', '3ro', '4to');
+CREATE TYPE semester AS ENUM ('1ero', '2do');
+
 CREATE TABLE asignaturas (
     id SERIAL PRIMARY KEY,
     name VARCHAR(100) NOT NULL,
-    year VARCHAR(150) NOT NULL,
-    semester VARCHAR(255) NOT NULL
+    year academic_year NOT NULL,
+    semester semester NOT NULL,
+    carrera_id INTEGER REFERENCES carreras(id) ON DELETE RESTRICT
+);
+
+CREATE INDEX asignaturas_year_semester_idx ON asignaturas (year, semester);
+
+CREATE TABLE balances (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES usuarios(id) ON DELETE CASCADE,
+    academic_year VARCHAR(10) NOT NULL,
+    period VARCHAR(10) NOT NULL,
+    school_year VARCHAR(20) NOT NULL,
+    start_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'borrador'
+        CHECK (status IN ('borrador', 'enviado', 'aprobado', 'rechazado')),
+    carrera_id INTEGER REFERENCES carreras(id) ON DELETE RESTRICT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Horas por semana y asignatura de un balance.
+-- Semanas: 1-15 lectivas, 16 consultas, 17 exámenes finales, 18 extra I, 19 extra II
+CREATE TABLE asignaciones_horas (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE RESTRICT,
+    week INTEGER NOT NULL CHECK (week BETWEEN 1 AND 19),
+    c INTEGER NOT NULL DEFAULT 0,
+    cp INTEGER NOT NULL DEFAULT 0,
+    s INTEGER NOT NULL DEFAULT 0,
+    pl INTEGER NOT NULL DEFAULT 0,
+    te INTEGER NOT NULL DEFAULT 0,
+    t INTEGER NOT NULL DEFAULT 0,
+    pp INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (balance_id, asignatura_id, week)
+);
+
+-- Feriados, vacaciones y eventos institucionales (p. ej. preparación militar)
+CREATE TABLE dias_no_lectivos (
+    id SERIAL PRIMARY KEY,
+    description VARCHAR(255) NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('feriado', 'vacaciones', 'evento')),
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL CHECK (end_date >= start_date),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Umbrales de validación de carga por año académico
+CREATE TABLE umbrales_carga (
+    academic_year VARCHAR(10) PRIMARY KEY,
+    max_weekly_hours INTEGER NOT NULL CHECK (max_weekly_hours > 0),
+    max_weekly_pp INTEGER NOT NULL CHECK (max_weekly_pp >= 0)
+);
+
+-- Revisiones inmutables de un balance (una por cada guardado)
+CREATE TABLE balance_revisiones (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES usuarios(id),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    snapshot JSONB NOT NULL,
+    UNIQUE (balance_id, revision)
+);
+
+-- Historial de cambios de estado de un balance (flujo de aprobación)
+CREATE TABLE balance_transiciones (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    from_status VARCHAR(20) NOT NULL,
+    to_status VARCHAR(20) NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES usuarios(id),
+    comment TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Profesores (opcionalmente vinculados a una cuenta de usuario)
+CREATE TABLE profesores (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    email VARCHAR(150),
+    usuario_id INTEGER UNIQUE REFERENCES usuarios(id) ON DELETE SET NULL,
+    max_weekly_hours INTEGER NOT NULL DEFAULT 16 CHECK (max_weekly_hours > 0),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Profesor que imparte cada tipo de actividad de una asignatura a un grupo
+CREATE TABLE asignaciones_profesores (
+    id SERIAL PRIMARY KEY,
+    profesor_id INTEGER NOT NULL REFERENCES profesores(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE CASCADE,
+    activity VARCHAR(2) NOT NULL CHECK (activity IN ('C', 'CP', 'S', 'PL', 'TE', 'T', 'PP')),
+    group_name VARCHAR(20) NOT NULL,
+    UNIQUE (asignatura_id, activity, group_name)
+);
+
+-- Grupos de estudiantes de cada año académico de una carrera. Las prácticas
+-- de laboratorio (PL) se imparten por subgrupos. Un balance puede tener sus
+-- propios grupos, que sustituyen a los del año.
+CREATE TABLE grupos_anio (
+    id SERIAL PRIMARY KEY,
+    carrera_id INTEGER NOT NULL REFERENCES carreras(id) ON DELETE CASCADE,
+    academic_year VARCHAR(10) NOT NULL,
+    name VARCHAR(20) NOT NULL,
+    subgroups INTEGER NOT NULL DEFAULT 1 CHECK (subgroups BETWEEN 1 AND 10),
+    UNIQUE (carrera_id, academic_year, name)
+);
+
+CREATE TABLE grupos (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    name VARCHAR(20) NOT NULL,
+    subgroups INTEGER NOT NULL DEFAULT 1 CHECK (subgroups BETWEEN 1 AND 10),
+    UNIQUE (balance_id, name)
+);
+
+-- Horas oficiales del plan de estudio por asignatura y tipo de actividad
+CREATE TABLE horas_plan (
+    id SERIAL PRIMARY KEY,
+    plan_id INTEGER NOT NULL REFERENCES planes_de_estudio(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE CASCADE,
+    c INTEGER NOT NULL DEFAULT 0,
+    cp INTEGER NOT NULL DEFAULT 0,
+    s INTEGER NOT NULL DEFAULT 0,
+    pl INTEGER NOT NULL DEFAULT 0,
+    te INTEGER NOT NULL DEFAULT 0,
+    t INTEGER NOT NULL DEFAULT 0,
+    pp INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (plan_id, asignatura_id)
+);
+
+-- Fechas exactas de las pruebas parciales (PP) de un balance
+CREATE TABLE fechas_evaluacion (
+    id SERIAL PRIMARY KEY,
+    balance_id INTEGER NOT NULL REFERENCES balances(id) ON DELETE CASCADE,
+    asignatura_id INTEGER NOT NULL REFERENCES asignaturas(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    UNIQUE (balance_id, asignatura_id, date)
+);
+
+-- Tokens de renovación (refresh). Solo se guarda el hash SHA-256; cada uso
+-- rota el token dentro de la misma familia y reutilizar uno ya usado revoca
+-- la familia completa.
+CREATE TABLE tokens_renovacion (
+    id SERIAL PRIMARY KEY,
+    usuario_id INTEGER NOT NULL REFERENCES usuarios(id) ON DELETE CASCADE,
+    family VARCHAR(64) NOT NULL,
+    token_hash CHAR(64) UNIQUE NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX idx_tokens_renovacion_family ON tokens_renovacion (family);
+
+-- Tokens de acceso revocados antes de caducar (logout). Se identifican por
+-- el claim `jti` y se pueden borrar una vez pasada su expiración.
+CREATE TABLE tokens_revocados (
+    jti VARCHAR(64) PRIMARY KEY,
+    usuario_id INTEGER NOT NULL REFERENCES usuarios(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
@@ -1,18 +1,69 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+use super::sea_orm_active_enums::AcademicYear;
+use super::sea_orm_active_enums::Semester;
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "asignaturas")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub name: String,
-    pub year: String,
-    pub semester: String,
+    pub year: AcademicYear,
+    pub semester: Semester,
+    pub carrera_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::asignaciones_horas::Entity")]
+    AsignacionesHoras,
+    #[sea_orm(has_many = "super::asignaciones_profesores::Entity")]
+    AsignacionesProfesores,
+    #[sea_orm(
+        belongs_to = "super::carreras::Entity",
+        from = "Column::CarreraId",
+        to = "super::carreras::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Restrict"
+    )]
+    Carreras,
+    #[sea_orm(has_many = "super::fechas_evaluacion::Entity")]
+    FechasEvaluacion,
+    #[sea_orm(has_many = "super::horas_plan::Entity")]
+    HorasPlan,
+}
+
+impl Related<super::asignaciones_horas::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AsignacionesHoras.def()
+    }
+}
+
+impl Related<super::asignaciones_profesores::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AsignacionesProfesores.def()
+    }
+}
+
+impl Related<super::carreras::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Carreras.def()
+    }
+}
+
+impl Related<super::fechas_evaluacion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FechasEvaluacion.def()
+    }
+}
+
+impl Related<super::horas_plan::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HorasPlan.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
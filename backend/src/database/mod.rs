@@ -2,5 +2,26 @@
 
 pub mod prelude;
 
+pub mod asignaciones_horas;
+pub mod asignaciones_profesores;
 pub mod asignaturas;
+pub mod balance_revisiones;
+pub mod balance_transiciones;
+pub mod balances;
+pub mod carreras;
+pub mod dias_no_lectivos;
+pub mod facultades;
+pub mod fechas_evaluacion;
+pub mod grupos;
+pub mod grupos_anio;
+pub mod horas_plan;
+pub mod permisos;
+pub mod planes_de_estudio;
+pub mod profesores;
+pub mod roles;
+pub mod roles_permisos;
+pub mod sea_orm_active_enums;
+pub mod tokens_renovacion;
+pub mod tokens_revocados;
+pub mod umbrales_carga;
 pub mod usuarios;
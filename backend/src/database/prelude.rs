@@ -1,4 +1,24 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+pub use super::asignaciones_horas::Entity as AsignacionesHoras;
+pub use super::asignaciones_profesores::Entity as AsignacionesProfesores;
 pub use super::asignaturas::Entity as Asignaturas;
+pub use super::balance_revisiones::Entity as BalanceRevisiones;
+pub use super::balance_transiciones::Entity as BalanceTransiciones;
+pub use super::balances::Entity as Balances;
+pub use super::carreras::Entity as Carreras;
+pub use super::dias_no_lectivos::Entity as DiasNoLectivos;
+pub use super::facultades::Entity as Facultades;
+pub use super::fechas_evaluacion::Entity as FechasEvaluacion;
+pub use super::grupos::Entity as Grupos;
+pub use super::grupos_anio::Entity as GruposAnio;
+pub use super::horas_plan::Entity as HorasPlan;
+pub use super::permisos::Entity as Permisos;
+pub use super::planes_de_estudio::Entity as PlanesDeEstudio;
+pub use super::profesores::Entity as Profesores;
+pub use super::roles::Entity as Roles;
+pub use super::roles_permisos::Entity as RolesPermisos;
+pub use super::tokens_renovacion::Entity as TokensRenovacion;
+pub use super::tokens_revocados::Entity as TokensRevocados;
+pub use super::umbrales_carga::Entity as UmbralesCarga;
 pub use super::usuarios::Entity as Usuarios;
@@ -13,9 +13,75 @@ pub struct Model {
     pub token: String,
     pub created_at: Option<DateTime>,
     pub isadmin: Option<bool>,
+    pub can_write: Option<bool>,
+    pub is_approver: Option<bool>,
+    pub rol_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::balance_revisiones::Entity")]
+    BalanceRevisiones,
+    #[sea_orm(has_many = "super::balance_transiciones::Entity")]
+    BalanceTransiciones,
+    #[sea_orm(has_many = "super::balances::Entity")]
+    Balances,
+    #[sea_orm(has_one = "super::profesores::Entity")]
+    Profesores,
+    #[sea_orm(
+        belongs_to = "super::roles::Entity",
+        from = "Column::RolId",
+        to = "super::roles::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Roles,
+    #[sea_orm(has_many = "super::tokens_renovacion::Entity")]
+    TokensRenovacion,
+    #[sea_orm(has_many = "super::tokens_revocados::Entity")]
+    TokensRevocados,
+}
+
+impl Related<super::balance_revisiones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BalanceRevisiones.def()
+    }
+}
+
+impl Related<super::balance_transiciones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BalanceTransiciones.def()
+    }
+}
+
+impl Related<super::balances::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Balances.def()
+    }
+}
+
+impl Related<super::profesores::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Profesores.def()
+    }
+}
+
+impl Related<super::roles::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Roles.def()
+    }
+}
+
+impl Related<super::tokens_renovacion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TokensRenovacion.def()
+    }
+}
+
+impl Related<super::tokens_revocados::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TokensRevocados.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,3 +1,5 @@
+#![allow(non_snake_case)]
+
 // Re-exportar las bibliotecas principales;
 use rocket::Build;
 pub use rocket;
@@ -9,6 +11,7 @@ pub use rocket::http::Status;
 pub use rocket::response::Redirect;
 pub use rocket::serde::json::Json;
 use rocket::Rocket;
+use sea_orm::DbErr;
 pub use rocket::State;
 pub use rocket::form::FromForm;
 pub use sea_orm::{Database, DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait};
@@ -23,34 +26,230 @@ pub mod utils;
 pub mod routes;
 
 // Re-exportar los módulos específicos de entidades para facilitar el acceso
-pub use database::{asignaturas, usuarios};
+pub use database::{asignaciones_horas, asignaciones_profesores, asignaturas, balance_revisiones, balance_transiciones, balances, carreras, dias_no_lectivos, facultades, fechas_evaluacion, grupos, grupos_anio, horas_plan, permisos, planes_de_estudio, profesores, roles, roles_permisos, sea_orm_active_enums, tokens_renovacion, tokens_revocados, umbrales_carga, usuarios};
 
 // Importar las rutas para usar en el macro routes!
 use routes::login::{
     login_get,
     login_form,
     login_json,
+    refresh,
     balance_page,
     logout,
+    api_logout,
     unauthorized
 };
+use routes::balances::{
+    list_balances,
+    create_balance,
+    get_balance,
+    update_balance,
+    delete_balance,
+    get_allocations,
+    save_allocations,
+    get_totals,
+    get_groups,
+    save_groups
+};
+use routes::calendar::{generate_dates, balance_calendar};
+use routes::export::{export_xlsx, export_pdf, export_report_pdf};
+use routes::holidays::{
+    list_holidays,
+    create_holiday,
+    update_holiday,
+    delete_holiday
+};
+use routes::import::import_data;
+use routes::revisions::{
+    list_revisions,
+    get_revision,
+    diff_revisions,
+    restore_revision
+};
+use routes::validation::{validate, list_thresholds, update_thresholds};
+use routes::workflow::{
+    submit_balance,
+    approve_balance,
+    reject_balance,
+    reopen_balance,
+    list_transitions,
+    pending_balances
+};
+use routes::careers::{
+    list_faculties,
+    create_faculty,
+    update_faculty,
+    delete_faculty,
+    list_careers,
+    create_career,
+    update_career,
+    delete_career,
+    get_year_groups,
+    save_year_groups,
+    list_curricula,
+    create_curriculum,
+    update_curriculum,
+    delete_curriculum
+};
+use routes::professors::{
+    list_professors,
+    create_professor,
+    update_professor,
+    delete_professor,
+    list_assignments,
+    create_assignment,
+    delete_assignment,
+    professors_load,
+    professor_load
+};
+use routes::dashboard::dashboard;
+use routes::subjects::{
+    list_subjects,
+    get_subject,
+    create_subject,
+    update_subject,
+    delete_subject
+};
+use routes::curriculum::{
+    get_targets,
+    save_targets,
+    plan_deviations
+};
+use routes::clone::clone_balance;
+use routes::reports::compare_report;
+use routes::evaluations::{
+    check_balance_evaluations,
+    get_evaluation_dates,
+    save_evaluation_dates
+};
+use routes::roles::{
+    list_permissions,
+    list_roles,
+    create_role,
+    update_role,
+    delete_role,
+    set_user_role
+};
+use routes::jwks::jwks;
 
 pub struct AppState {
     pub db: DatabaseConnection,
+    /// Tokens revocados (logout) con caché en memoria
+    pub revoked: utils::revocation::RevocationStore,
 }
 
-pub async fn run() -> Rocket<Build> {
+pub async fn run() -> Result<Rocket<Build>, DbErr> {
     let db = utils::db::establish_connection().await;
-    rocket::build()
-        .manage(AppState { db })
+    utils::keys::init_keys();
+    let revoked = utils::revocation::RevocationStore::default();
+    revoked.load(&db).await?;
+    Ok(rocket::build()
+        .manage(AppState { db, revoked })
         .mount("/", routes![
             // Rutas públicas
             login_get,
             login_form,
             logout,
             balance_page,
-            login_json
+            login_json,
+            refresh,
+            api_logout,
+            // Balances (requieren autenticación)
+            list_balances,
+            create_balance,
+            get_balance,
+            update_balance,
+            delete_balance,
+            get_allocations,
+            save_allocations,
+            get_totals,
+            get_groups,
+            save_groups,
+            // Calendario académico
+            generate_dates,
+            balance_calendar,
+            // Días no lectivos (escritura con permiso settings.manage)
+            list_holidays,
+            create_holiday,
+            update_holiday,
+            delete_holiday,
+            // Exportación
+            export_xlsx,
+            export_pdf,
+            export_report_pdf,
+            // Importación
+            import_data,
+            // Validación de carga
+            validate,
+            list_thresholds,
+            update_thresholds,
+            // Historial de revisiones
+            list_revisions,
+            get_revision,
+            diff_revisions,
+            restore_revision,
+            // Flujo de aprobación
+            submit_balance,
+            approve_balance,
+            reject_balance,
+            reopen_balance,
+            list_transitions,
+            pending_balances,
+            // Facultades, carreras y planes de estudio
+            list_faculties,
+            create_faculty,
+            update_faculty,
+            delete_faculty,
+            list_careers,
+            create_career,
+            update_career,
+            delete_career,
+            get_year_groups,
+            save_year_groups,
+            list_curricula,
+            create_curriculum,
+            update_curriculum,
+            delete_curriculum,
+            // Profesores y carga docente
+            list_professors,
+            create_professor,
+            update_professor,
+            delete_professor,
+            list_assignments,
+            create_assignment,
+            delete_assignment,
+            professors_load,
+            professor_load,
+            // Dashboard
+            dashboard,
+            // Catálogo de asignaturas (escritura con catalog.write)
+            list_subjects,
+            get_subject,
+            create_subject,
+            update_subject,
+            delete_subject,
+            // Horas del plan de estudio y desviaciones
+            get_targets,
+            save_targets,
+            plan_deviations,
+            // Copia de balances
+            clone_balance,
+            // Comparación de balances
+            compare_report,
+            // Pruebas parciales (PP)
+            check_balance_evaluations,
+            get_evaluation_dates,
+            save_evaluation_dates,
+            // Roles y permisos
+            list_permissions,
+            list_roles,
+            create_role,
+            update_role,
+            delete_role,
+            set_user_role,
+            // Claves públicas JWT (JWKS)
+            jwks
         ])
         .register("/", catchers![unauthorized])
-        .mount("/frontend", FileServer::from("../frontend"))
+        .mount("/frontend", FileServer::from("../frontend")))
 }
\ No newline at end of file
@@ -4,5 +4,7 @@ use WebProyect::run;
 
 #[launch]
 async fn rocket() -> _ {
-    run().await
+    run()
+        .await
+        .expect("Error al cargar los tokens revocados")
 }
@@ -0,0 +1,546 @@
+use crate::*;
+use crate::utils::jwt::{AuthenticatedUser, Claims};
+use crate::utils::permissions::Permission;
+use crate::utils::revisions::{record_revision, GroupSnapshot};
+use crate::utils::totals::{self, AllocationCell, BalanceTotals};
+use crate::utils::workflow::BalanceStatus;
+use rocket::{get, post, put, delete};
+use sea_orm::{
+    ConnectionTrait, DbErr, ModelTrait, PaginatorTrait, QueryOrder, QuerySelect, Select,
+    TransactionTrait,
+};
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+
+/// Años académicos admitidos (1ro.ICS - 4to.ICS)
+pub const ACADEMIC_YEARS: [&str; 4] = ["1ro", "2do", "3ro", "4to"];
+
+/// Períodos admitidos
+pub const PERIODS: [&str; 2] = ["1ero", "2do"];
+
+/// Datos del formulario de balance (panel de control de balance.html)
+#[derive(Deserialize)]
+pub struct BalanceInput {
+    academic_year: String,
+    period: String,
+    school_year: String,
+    start_date: Date,
+    #[serde(default)]
+    carrera_id: Option<i32>,
+}
+
+impl BalanceInput {
+    /// Comprueba que el año, el período y el año lectivo tengan valores válidos
+    fn is_valid(&self) -> bool {
+        ACADEMIC_YEARS.contains(&self.academic_year.as_str())
+            && PERIODS.contains(&self.period.as_str())
+            && !self.school_year.trim().is_empty()
+    }
+
+    /// Comprueba que la carrera exista y tenga el año académico del balance
+    async fn check_career(&self, db: &DatabaseConnection) -> Result<(), Status> {
+        let Some(carrera_id) = self.carrera_id else {
+            return Ok(());
+        };
+        let career = carreras::Entity::find_by_id(carrera_id)
+            .one(db)
+            .await
+            .map_err(|_| Status::InternalServerError)?
+            .ok_or(Status::BadRequest)?;
+
+        let year = ACADEMIC_YEARS
+            .iter()
+            .position(|y| *y == self.academic_year)
+            .unwrap_or(usize::MAX);
+        if year >= career.years as usize {
+            return Err(Status::BadRequest);
+        }
+        Ok(())
+    }
+}
+
+/// Reemplaza todas las horas de un balance por las celdas indicadas
+/// (solo se guardan las celdas con horas)
+pub async fn replace_allocations<C: ConnectionTrait>(
+    db: &C,
+    balance_id: i32,
+    cells: &[AllocationCell],
+) -> Result<(), DbErr> {
+    asignaciones_horas::Entity::delete_many()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance_id))
+        .exec(db)
+        .await?;
+
+    let rows: Vec<asignaciones_horas::ActiveModel> = cells
+        .iter()
+        .filter(|a| !a.hours.is_empty())
+        .map(|a| asignaciones_horas::ActiveModel {
+            balance_id: Set(balance_id),
+            asignatura_id: Set(a.asignatura_id),
+            week: Set(a.week),
+            c: Set(a.hours.c),
+            cp: Set(a.hours.cp),
+            s: Set(a.hours.s),
+            pl: Set(a.hours.pl),
+            te: Set(a.hours.te),
+            t: Set(a.hours.t),
+            pp: Set(a.hours.pp),
+            ..Default::default()
+        })
+        .collect();
+
+    if !rows.is_empty() {
+        asignaciones_horas::Entity::insert_many(rows).exec(db).await?;
+    }
+
+    Ok(())
+}
+
+/// Reemplaza los grupos de un balance
+pub async fn replace_groups<C: ConnectionTrait>(
+    db: &C,
+    balance_id: i32,
+    groups: &[GroupSnapshot],
+) -> Result<(), DbErr> {
+    grupos::Entity::delete_many()
+        .filter(grupos::Column::BalanceId.eq(balance_id))
+        .exec(db)
+        .await?;
+
+    if !groups.is_empty() {
+        let models = groups.iter().map(|g| grupos::ActiveModel {
+            balance_id: Set(balance_id),
+            name: Set(g.name.clone()),
+            subgroups: Set(g.subgroups),
+            ..Default::default()
+        });
+        grupos::Entity::insert_many(models).exec(db).await?;
+    }
+
+    Ok(())
+}
+
+/// Busca un balance y verifica que pertenezca al usuario (o que pueda
+/// modificar los de todos)
+pub async fn find_owned_balance(
+    db: &DatabaseConnection,
+    id: i32,
+    claims: &Claims,
+) -> Result<balances::Model, Status> {
+    let balance = balances::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    if balance.user_id != claims.user_id() && !claims.has(Permission::EditAllBalances) {
+        return Err(Status::Forbidden);
+    }
+
+    Ok(balance)
+}
+
+/// Consulta de los balances que el usuario puede ver: los suyos, o todos si
+/// tiene el permiso `balances.view_all`
+pub fn visible_balances(claims: &Claims, carrera_id: Option<i32>) -> Select<balances::Entity> {
+    let mut query = balances::Entity::find();
+    if !claims.has(Permission::ViewAllBalances) {
+        query = query.filter(balances::Column::UserId.eq(claims.user_id()));
+    }
+    if let Some(carrera_id) = carrera_id {
+        query = query.filter(balances::Column::CarreraId.eq(carrera_id));
+    }
+    query
+}
+
+/// Busca un balance para consultarlo: además del dueño, quien tenga el
+/// permiso `balances.view_all` (p. ej. los aprobadores que los revisan)
+pub async fn find_visible_balance(
+    db: &DatabaseConnection,
+    id: i32,
+    claims: &Claims,
+) -> Result<balances::Model, Status> {
+    let balance = balances::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    if balance.user_id != claims.user_id() && !claims.has(Permission::ViewAllBalances) {
+        return Err(Status::Forbidden);
+    }
+
+    Ok(balance)
+}
+
+/// Un balance aprobado es de solo lectura hasta que se reabre
+fn check_editable(balance: &balances::Model) -> Result<(), Status> {
+    let editable = BalanceStatus::parse(&balance.status).is_none_or(|s| s.is_editable());
+    if !editable {
+        return Err(Status::Conflict);
+    }
+    Ok(())
+}
+
+/// Busca un balance para modificarlo: debe pertenecer al usuario y no estar aprobado
+pub async fn find_editable_balance(
+    db: &DatabaseConnection,
+    id: i32,
+    claims: &Claims,
+) -> Result<balances::Model, Status> {
+    let balance = find_owned_balance(db, id, claims).await?;
+    check_editable(&balance)?;
+
+    Ok(balance)
+}
+
+/// Bloquea la fila del balance hasta el final de la transacción y vuelve a
+/// comprobar que sea editable: si se aprobó después de `find_editable_balance`,
+/// el guardado se rechaza en lugar de modificar un balance aprobado.
+pub async fn lock_editable_balance<C: ConnectionTrait>(
+    db: &C,
+    id: i32,
+) -> Result<balances::Model, Status> {
+    let balance = balances::Entity::find_by_id(id)
+        .lock_exclusive()
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+    check_editable(&balance)?;
+
+    Ok(balance)
+}
+
+/// Lista los balances del usuario (o todos con `balances.view_all`),
+/// opcionalmente de una carrera
+#[get("/api/balances?<carrera_id>")]
+pub async fn list_balances(
+    user: AuthenticatedUser,
+    carrera_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<balances::Model>>, Status> {
+    let list = visible_balances(&user.0, carrera_id)
+        .order_by_desc(balances::Column::UpdatedAt)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Crea un nuevo balance para el usuario autenticado
+#[post("/api/balances", format = "json", data = "<input>")]
+pub async fn create_balance(
+    user: AuthenticatedUser,
+    input: Json<BalanceInput>,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+    input.check_career(&db.db).await?;
+
+    let now = chrono::Local::now().naive_local();
+    let balance = balances::ActiveModel {
+        user_id: Set(user.0.user_id()),
+        academic_year: Set(input.academic_year.clone()),
+        period: Set(input.period.clone()),
+        school_year: Set(input.school_year.trim().to_string()),
+        start_date: Set(input.start_date),
+        status: Set(BalanceStatus::Borrador.as_str().to_string()),
+        carrera_id: Set(input.carrera_id),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+        ..Default::default()
+    };
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    let saved = balance
+        .insert(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    record_revision(&txn, &saved, user.0.user_id())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(saved))
+}
+
+/// Carga un balance guardado
+#[get("/api/balances/<id>")]
+pub async fn get_balance(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+    Ok(Json(balance))
+}
+
+/// Actualiza los datos generales de un balance
+#[put("/api/balances/<id>", format = "json", data = "<input>")]
+pub async fn update_balance(
+    user: AuthenticatedUser,
+    id: i32,
+    input: Json<BalanceInput>,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+    input.check_career(&db.db).await?;
+
+    let balance = find_editable_balance(&db.db, id, &user.0).await?;
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    let balance = lock_editable_balance(&txn, balance.id).await?;
+
+    let mut active: balances::ActiveModel = balance.into();
+    active.academic_year = Set(input.academic_year.clone());
+    active.period = Set(input.period.clone());
+    active.school_year = Set(input.school_year.trim().to_string());
+    active.start_date = Set(input.start_date);
+    if input.carrera_id.is_some() {
+        active.carrera_id = Set(input.carrera_id);
+    }
+    active.updated_at = Set(Some(chrono::Local::now().naive_local()));
+
+    let updated = active
+        .update(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    record_revision(&txn, &updated, user.0.user_id())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina un balance
+#[delete("/api/balances/<id>")]
+pub async fn delete_balance(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let balance = find_editable_balance(&db.db, id, &user.0).await?;
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    let balance = lock_editable_balance(&txn, balance.id).await?;
+    balance
+        .delete(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
+
+/// Devuelve las horas asignadas por semana y asignatura
+#[get("/api/balances/<id>/allocations")]
+pub async fn get_allocations(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<Vec<asignaciones_horas::Model>>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .order_by_asc(asignaciones_horas::Column::AsignaturaId)
+        .order_by_asc(asignaciones_horas::Column::Week)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(allocations))
+}
+
+/// Guarda la tabla completa del balance (reemplaza las horas anteriores)
+/// y devuelve los totales calculados en el servidor
+#[put("/api/balances/<id>/allocations", format = "json", data = "<input>")]
+pub async fn save_allocations(
+    user: AuthenticatedUser,
+    id: i32,
+    input: Json<Vec<AllocationCell>>,
+    db: &State<AppState>,
+) -> Result<Json<BalanceTotals>, Status> {
+    let balance = find_editable_balance(&db.db, id, &user.0).await?;
+
+    if input
+        .iter()
+        .any(|a| {
+            !totals::is_valid_week(a.week) || a.hours.has_negative() || a.hours.exceeds_cell_max()
+        })
+        || totals::has_duplicate_cells(&input)
+    {
+        return Err(Status::BadRequest);
+    }
+
+    // Todas las asignaturas referenciadas deben existir
+    let mut subject_ids: Vec<i32> = input.iter().map(|a| a.asignatura_id).collect();
+    subject_ids.sort_unstable();
+    subject_ids.dedup();
+    let found = asignaturas::Entity::find()
+        .filter(asignaturas::Column::Id.is_in(subject_ids.clone()))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if found.len() != subject_ids.len() {
+        return Err(Status::UnprocessableEntity);
+    }
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    let balance = lock_editable_balance(&txn, balance.id).await?;
+
+    replace_allocations(&txn, balance.id, &input)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let mut active: balances::ActiveModel = balance.clone().into();
+    active.updated_at = Set(Some(chrono::Local::now().naive_local()));
+    let updated = active
+        .update(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    record_revision(&txn, &updated, user.0.user_id())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    let totals = totals::totals_for_balance(&db.db, &balance)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(totals))
+}
+
+/// Calcula los totales del balance ("Calcular Totales")
+#[get("/api/balances/<id>/totals")]
+pub async fn get_totals(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<BalanceTotals>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let totals = totals::totals_for_balance(&db.db, &balance)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(totals))
+}
+
+/// Grupo de estudiantes del año y número de subgrupos de laboratorio
+#[derive(Deserialize)]
+pub struct GroupInput {
+    name: String,
+    #[serde(default = "default_subgroups")]
+    subgroups: i32,
+}
+
+fn default_subgroups() -> i32 {
+    1
+}
+
+/// Valida una lista de grupos: nombres no vacíos (hasta 20 caracteres) y sin
+/// repetir, y de 1 a 10 subgrupos. Los nombres se guardan en mayúsculas.
+pub fn parse_groups(input: &[GroupInput]) -> Result<Vec<GroupSnapshot>, Status> {
+    let groups: Vec<GroupSnapshot> = input
+        .iter()
+        .map(|g| GroupSnapshot {
+            name: g.name.trim().to_uppercase(),
+            subgroups: g.subgroups,
+        })
+        .collect();
+    if groups
+        .iter()
+        .any(|g| g.name.is_empty() || g.name.len() > 20 || !(1..=10).contains(&g.subgroups))
+    {
+        return Err(Status::BadRequest);
+    }
+
+    let mut names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    if names.len() != groups.len() {
+        return Err(Status::BadRequest);
+    }
+    Ok(groups)
+}
+
+/// Grupos que se aplican a un balance. `own` indica si son los propios del
+/// balance; si no, son los del año académico de su carrera.
+#[derive(Serialize)]
+pub struct BalanceGroups {
+    pub own: bool,
+    pub groups: Vec<GroupSnapshot>,
+}
+
+/// Lista los grupos del balance: los propios o, si no tiene, los del año
+#[get("/api/balances/<id>/groups")]
+pub async fn get_groups(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<BalanceGroups>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let own = grupos::Entity::find()
+        .filter(grupos::Column::BalanceId.eq(balance.id))
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let groups = totals::balance_groups(&db.db, &balance)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(BalanceGroups {
+        own: own > 0,
+        groups,
+    }))
+}
+
+/// Reemplaza los grupos propios del balance (sustituyen a los del año) y
+/// devuelve los totales recalculados. Una lista vacía vuelve a los del año.
+#[put("/api/balances/<id>/groups", format = "json", data = "<input>")]
+pub async fn save_groups(
+    user: AuthenticatedUser,
+    id: i32,
+    input: Json<Vec<GroupInput>>,
+    db: &State<AppState>,
+) -> Result<Json<BalanceTotals>, Status> {
+    let balance = find_editable_balance(&db.db, id, &user.0).await?;
+    let groups = parse_groups(&input)?;
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    let balance = lock_editable_balance(&txn, balance.id).await?;
+
+    replace_groups(&txn, balance.id, &groups)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    // Los grupos cambian las horas del profesorado: el cambio queda en el
+    // historial como cualquier otro guardado
+    let mut active: balances::ActiveModel = balance.clone().into();
+    active.updated_at = Set(Some(chrono::Local::now().naive_local()));
+    let updated = active
+        .update(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    record_revision(&txn, &updated, user.0.user_id())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    let totals = totals::totals_for_balance(&db.db, &balance)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(totals))
+}
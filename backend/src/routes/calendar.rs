@@ -0,0 +1,70 @@
+use crate::*;
+use crate::routes::balances::find_visible_balance;
+use crate::utils::calendar::{
+    generate_calendar, load_non_teaching, starts_in_period, CalendarWeek,
+};
+use crate::utils::jwt::AuthenticatedUser;
+use rocket::{get, post};
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+
+/// Datos del panel de control necesarios para "Generar Fechas". La fecha de
+/// inicio debe corresponder al período ("1ero" o "2do").
+#[derive(Deserialize)]
+pub struct CalendarRequest {
+    start_date: Date,
+    period: String,
+    /// Semanas no lectivas (cualquier fecha dentro de la semana)
+    #[serde(default)]
+    non_teaching_weeks: Vec<Date>,
+}
+
+#[derive(Serialize)]
+pub struct CalendarResponse {
+    pub period: String,
+    pub start_date: Date,
+    pub weeks: Vec<CalendarWeek>,
+}
+
+/// Genera las fechas reales de cada columna de semana del balance,
+/// teniendo en cuenta los días no lectivos registrados
+#[post("/api/calendar/generate", format = "json", data = "<input>")]
+pub async fn generate_dates(
+    _user: AuthenticatedUser,
+    input: Json<CalendarRequest>,
+    db: &State<AppState>,
+) -> Result<Json<CalendarResponse>, Status> {
+    if !starts_in_period(&input.period, input.start_date) {
+        return Err(Status::BadRequest);
+    }
+
+    let non_teaching = load_non_teaching(&db.db, input.start_date)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(CalendarResponse {
+        period: input.period.clone(),
+        start_date: input.start_date,
+        weeks: generate_calendar(input.start_date, &input.non_teaching_weeks, &non_teaching),
+    }))
+}
+
+/// Calendario de un balance guardado, con las semanas que pierden días marcadas
+#[get("/api/balances/<id>/calendar")]
+pub async fn balance_calendar(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<CalendarResponse>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let non_teaching = load_non_teaching(&db.db, balance.start_date)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(CalendarResponse {
+        period: balance.period,
+        start_date: balance.start_date,
+        weeks: generate_calendar(balance.start_date, &[], &non_teaching),
+    }))
+}
@@ -0,0 +1,454 @@
+use crate::*;
+use crate::routes::balances::{parse_groups, GroupInput, ACADEMIC_YEARS};
+use crate::utils::db::write_error;
+use crate::utils::jwt::{AuthenticatedUser, Require};
+use crate::utils::permissions::perm;
+use rocket::{get, post, put, delete};
+use sea_orm::{ModelTrait, PaginatorTrait, QueryOrder, TransactionTrait};
+use serde::Deserialize;
+
+/// Datos de una facultad
+#[derive(Deserialize)]
+pub struct FacultyInput {
+    name: String,
+}
+
+/// Datos de una carrera
+#[derive(Deserialize)]
+pub struct CareerInput {
+    facultad_id: i32,
+    name: String,
+    code: String,
+    years: i32,
+}
+
+/// Datos de un plan de estudio
+#[derive(Deserialize)]
+pub struct CurriculumInput {
+    carrera_id: i32,
+    name: String,
+    school_year: String,
+    #[serde(default = "default_active")]
+    is_active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl CareerInput {
+    /// El código es el sufijo de los años (1ro.ICS), sin puntos ni espacios
+    fn is_valid(&self) -> bool {
+        let code = self.code.trim();
+        !self.name.trim().is_empty()
+            && !code.is_empty()
+            && code.len() <= 10
+            && !code.contains(['.', ' '])
+            && (1..=4).contains(&self.years)
+    }
+}
+
+// ========================================
+// FACULTADES
+// ========================================
+
+/// Lista las facultades
+#[get("/api/faculties")]
+pub async fn list_faculties(
+    _user: AuthenticatedUser,
+    db: &State<AppState>,
+) -> Result<Json<Vec<facultades::Model>>, Status> {
+    let list = facultades::Entity::find()
+        .order_by_asc(facultades::Column::Name)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Registra una facultad - Requiere permiso de gestión académica
+#[post("/api/faculties", format = "json", data = "<input>")]
+pub async fn create_faculty(
+    _admin: Require<perm::ManageAcademic>,
+    input: Json<FacultyInput>,
+    db: &State<AppState>,
+) -> Result<Json<facultades::Model>, Status> {
+    if input.name.trim().is_empty() {
+        return Err(Status::BadRequest);
+    }
+
+    let saved = facultades::ActiveModel {
+        name: Set(input.name.trim().to_string()),
+        created_at: Set(Some(chrono::Local::now().naive_local())),
+        ..Default::default()
+    }
+    .insert(&db.db)
+    .await
+    .map_err(write_error)?;
+
+    Ok(Json(saved))
+}
+
+/// Modifica una facultad - Requiere permiso de gestión académica
+#[put("/api/faculties/<id>", format = "json", data = "<input>")]
+pub async fn update_faculty(
+    _admin: Require<perm::ManageAcademic>,
+    id: i32,
+    input: Json<FacultyInput>,
+    db: &State<AppState>,
+) -> Result<Json<facultades::Model>, Status> {
+    if input.name.trim().is_empty() {
+        return Err(Status::BadRequest);
+    }
+
+    let faculty = facultades::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: facultades::ActiveModel = faculty.into();
+    active.name = Set(input.name.trim().to_string());
+    let updated = active.update(&db.db).await.map_err(write_error)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina una facultad sin carreras - Requiere permiso de gestión académica
+#[delete("/api/faculties/<id>")]
+pub async fn delete_faculty(
+    _admin: Require<perm::ManageAcademic>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let faculty = facultades::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let careers = faculty
+        .find_related(carreras::Entity)
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if careers > 0 {
+        return Err(Status::Conflict);
+    }
+
+    faculty
+        .delete(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
+
+// ========================================
+// CARRERAS
+// ========================================
+
+/// Lista las carreras, opcionalmente de una facultad
+#[get("/api/careers?<facultad_id>")]
+pub async fn list_careers(
+    _user: AuthenticatedUser,
+    facultad_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<carreras::Model>>, Status> {
+    let mut query = carreras::Entity::find().order_by_asc(carreras::Column::Name);
+    if let Some(facultad_id) = facultad_id {
+        query = query.filter(carreras::Column::FacultadId.eq(facultad_id));
+    }
+
+    let list = query
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Registra una carrera - Requiere permiso de gestión académica
+#[post("/api/careers", format = "json", data = "<input>")]
+pub async fn create_career(
+    _admin: Require<perm::ManageAcademic>,
+    input: Json<CareerInput>,
+    db: &State<AppState>,
+) -> Result<Json<carreras::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let saved = carreras::ActiveModel {
+        facultad_id: Set(input.facultad_id),
+        name: Set(input.name.trim().to_string()),
+        code: Set(input.code.trim().to_uppercase()),
+        years: Set(input.years),
+        created_at: Set(Some(chrono::Local::now().naive_local())),
+        ..Default::default()
+    }
+    .insert(&db.db)
+    .await
+    .map_err(write_error)?;
+
+    Ok(Json(saved))
+}
+
+/// Modifica una carrera - Requiere permiso de gestión académica
+#[put("/api/careers/<id>", format = "json", data = "<input>")]
+pub async fn update_career(
+    _admin: Require<perm::ManageAcademic>,
+    id: i32,
+    input: Json<CareerInput>,
+    db: &State<AppState>,
+) -> Result<Json<carreras::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let career = carreras::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: carreras::ActiveModel = career.into();
+    active.facultad_id = Set(input.facultad_id);
+    active.name = Set(input.name.trim().to_string());
+    active.code = Set(input.code.trim().to_uppercase());
+    active.years = Set(input.years);
+    let updated = active.update(&db.db).await.map_err(write_error)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina una carrera sin asignaturas ni balances - Requiere permiso de gestión académica
+#[delete("/api/careers/<id>")]
+pub async fn delete_career(
+    _admin: Require<perm::ManageAcademic>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let career = carreras::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let subjects = career
+        .find_related(asignaturas::Entity)
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let balances = career
+        .find_related(balances::Entity)
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if subjects > 0 || balances > 0 {
+        return Err(Status::Conflict);
+    }
+
+    career
+        .delete(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
+
+// ========================================
+// GRUPOS DEL AÑO
+// ========================================
+
+/// Busca una carrera y comprueba que tenga el año académico indicado
+async fn find_career_year(
+    db: &DatabaseConnection,
+    id: i32,
+    academic_year: &str,
+) -> Result<carreras::Model, Status> {
+    let career = carreras::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let year = ACADEMIC_YEARS
+        .iter()
+        .position(|y| *y == academic_year)
+        .ok_or(Status::BadRequest)?;
+    if year >= career.years as usize {
+        return Err(Status::BadRequest);
+    }
+    Ok(career)
+}
+
+async fn year_groups(
+    db: &DatabaseConnection,
+    carrera_id: i32,
+    academic_year: &str,
+) -> Result<Vec<grupos_anio::Model>, Status> {
+    grupos_anio::Entity::find()
+        .filter(grupos_anio::Column::CarreraId.eq(carrera_id))
+        .filter(grupos_anio::Column::AcademicYear.eq(academic_year))
+        .order_by_asc(grupos_anio::Column::Name)
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)
+}
+
+/// Grupos de un año académico de la carrera, que usan sus balances salvo
+/// que tengan grupos propios
+#[get("/api/careers/<id>/groups?<academic_year>")]
+pub async fn get_year_groups(
+    _user: AuthenticatedUser,
+    id: i32,
+    academic_year: &str,
+    db: &State<AppState>,
+) -> Result<Json<Vec<grupos_anio::Model>>, Status> {
+    let career = find_career_year(&db.db, id, academic_year).await?;
+
+    Ok(Json(year_groups(&db.db, career.id, academic_year).await?))
+}
+
+/// Reemplaza los grupos de un año académico de la carrera - Requiere
+/// permiso de gestión académica
+#[put("/api/careers/<id>/groups?<academic_year>", format = "json", data = "<input>")]
+pub async fn save_year_groups(
+    _manager: Require<perm::ManageAcademic>,
+    id: i32,
+    academic_year: &str,
+    input: Json<Vec<GroupInput>>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<grupos_anio::Model>>, Status> {
+    let career = find_career_year(&db.db, id, academic_year).await?;
+    let groups = parse_groups(&input)?;
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    grupos_anio::Entity::delete_many()
+        .filter(grupos_anio::Column::CarreraId.eq(career.id))
+        .filter(grupos_anio::Column::AcademicYear.eq(academic_year))
+        .exec(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    if !groups.is_empty() {
+        let models = groups.iter().map(|g| grupos_anio::ActiveModel {
+            carrera_id: Set(career.id),
+            academic_year: Set(academic_year.to_string()),
+            name: Set(g.name.clone()),
+            subgroups: Set(g.subgroups),
+            ..Default::default()
+        });
+        grupos_anio::Entity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(write_error)?;
+    }
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(year_groups(&db.db, career.id, academic_year).await?))
+}
+
+// ========================================
+// PLANES DE ESTUDIO
+// ========================================
+
+/// Lista los planes de estudio, opcionalmente de una carrera
+#[get("/api/curricula?<carrera_id>")]
+pub async fn list_curricula(
+    _user: AuthenticatedUser,
+    carrera_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<planes_de_estudio::Model>>, Status> {
+    let mut query = planes_de_estudio::Entity::find()
+        .order_by_asc(planes_de_estudio::Column::CarreraId)
+        .order_by_desc(planes_de_estudio::Column::SchoolYear);
+    if let Some(carrera_id) = carrera_id {
+        query = query.filter(planes_de_estudio::Column::CarreraId.eq(carrera_id));
+    }
+
+    let list = query
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Registra un plan de estudio - Requiere permiso de gestión académica
+#[post("/api/curricula", format = "json", data = "<input>")]
+pub async fn create_curriculum(
+    _admin: Require<perm::ManageAcademic>,
+    input: Json<CurriculumInput>,
+    db: &State<AppState>,
+) -> Result<Json<planes_de_estudio::Model>, Status> {
+    if input.name.trim().is_empty() || input.school_year.trim().is_empty() {
+        return Err(Status::BadRequest);
+    }
+
+    let saved = planes_de_estudio::ActiveModel {
+        carrera_id: Set(input.carrera_id),
+        name: Set(input.name.trim().to_string()),
+        school_year: Set(input.school_year.trim().to_string()),
+        is_active: Set(input.is_active),
+        created_at: Set(Some(chrono::Local::now().naive_local())),
+        ..Default::default()
+    }
+    .insert(&db.db)
+    .await
+    .map_err(write_error)?;
+
+    Ok(Json(saved))
+}
+
+/// Modifica un plan de estudio - Requiere permiso de gestión académica
+#[put("/api/curricula/<id>", format = "json", data = "<input>")]
+pub async fn update_curriculum(
+    _admin: Require<perm::ManageAcademic>,
+    id: i32,
+    input: Json<CurriculumInput>,
+    db: &State<AppState>,
+) -> Result<Json<planes_de_estudio::Model>, Status> {
+    if input.name.trim().is_empty() || input.school_year.trim().is_empty() {
+        return Err(Status::BadRequest);
+    }
+
+    let plan = planes_de_estudio::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: planes_de_estudio::ActiveModel = plan.into();
+    active.carrera_id = Set(input.carrera_id);
+    active.name = Set(input.name.trim().to_string());
+    active.school_year = Set(input.school_year.trim().to_string());
+    active.is_active = Set(input.is_active);
+    let updated = active.update(&db.db).await.map_err(write_error)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina un plan de estudio - Requiere permiso de gestión académica
+#[delete("/api/curricula/<id>")]
+pub async fn delete_curriculum(
+    _admin: Require<perm::ManageAcademic>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let plan = planes_de_estudio::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    plan.delete(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
@@ -0,0 +1,304 @@
+use crate::*;
+use crate::routes::balances::{find_visible_balance, ACADEMIC_YEARS, PERIODS};
+use crate::sea_orm_active_enums::{AcademicYear, Semester};
+use crate::utils::calendar::{generate_calendar, load_non_teaching, CalendarWeek};
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::revisions::record_revision;
+use crate::utils::workflow::BalanceStatus;
+use rocket::post;
+use sea_orm::prelude::Date;
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Datos del balance nuevo. El año académico y el período se mantienen si
+/// no se indican.
+#[derive(Deserialize)]
+pub struct CloneInput {
+    school_year: String,
+    start_date: Date,
+    academic_year: Option<String>,
+    period: Option<String>,
+    /// Reasignación manual de asignaturas: ID original -> ID nuevo
+    #[serde(default)]
+    remap: HashMap<i32, i32>,
+}
+
+/// Asignatura del balance original sustituida por otra en la copia
+#[derive(Serialize)]
+pub struct RemappedSubject {
+    pub from_id: i32,
+    pub to_id: i32,
+    pub name: String,
+}
+
+/// Asignatura del balance original cuyas horas no se copiaron
+#[derive(Serialize)]
+pub struct SkippedSubject {
+    pub asignatura_id: i32,
+    pub name: String,
+    pub reason: String,
+    pub cells: usize,
+}
+
+#[derive(Serialize)]
+pub struct CloneResponse {
+    pub balance: balances::Model,
+    pub copied_cells: usize,
+    pub remapped: Vec<RemappedSubject>,
+    pub skipped: Vec<SkippedSubject>,
+    /// Semanas con horas que en el nuevo calendario pierden días
+    pub reduced_weeks: Vec<i32>,
+    pub weeks: Vec<CalendarWeek>,
+}
+
+/// Clave para buscar una asignatura equivalente por nombre
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Copia un balance (horas y grupos propios) en un nuevo curso o fecha de inicio.
+///
+/// Las asignaturas que ya no corresponden al año y período del balance nuevo
+/// se sustituyen por la asignatura del mismo nombre en ese año y período (o
+/// por la indicada en `remap`, que debe ser de ese año, período y carrera);
+/// si no hay equivalente válida, sus horas no se copian y se informan en
+/// `skipped`. Las fechas de las semanas se regeneran a
+/// partir de la nueva fecha de inicio.
+#[post("/api/balances/<id>/clone", format = "json", data = "<input>")]
+pub async fn clone_balance(
+    user: AuthenticatedUser,
+    id: i32,
+    input: Json<CloneInput>,
+    db: &State<AppState>,
+) -> Result<Json<CloneResponse>, Status> {
+    let source = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let academic_year = input
+        .academic_year
+        .clone()
+        .unwrap_or_else(|| source.academic_year.clone());
+    let period = input.period.clone().unwrap_or_else(|| source.period.clone());
+    if !ACADEMIC_YEARS.contains(&academic_year.as_str())
+        || !PERIODS.contains(&period.as_str())
+        || input.school_year.trim().is_empty()
+    {
+        return Err(Status::BadRequest);
+    }
+    let year = AcademicYear::parse(&academic_year).ok_or(Status::BadRequest)?;
+    let semester = Semester::parse(&period).ok_or(Status::BadRequest)?;
+
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(source.id))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let groups = grupos::Entity::find()
+        .filter(grupos::Column::BalanceId.eq(source.id))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    // Asignaturas originales y candidatas del año y período del balance nuevo
+    let source_ids: BTreeSet<i32> = allocations.iter().map(|a| a.asignatura_id).collect();
+    let subjects: HashMap<i32, asignaturas::Model> = asignaturas::Entity::find()
+        .filter(
+            asignaturas::Column::Id
+                .is_in(source_ids.iter().copied().chain(input.remap.values().copied())),
+        )
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .into_iter()
+        .map(|s| (s.id, s))
+        .collect();
+    let mut candidates = asignaturas::Entity::find()
+        .filter(asignaturas::Column::Year.eq(year))
+        .filter(asignaturas::Column::Semester.eq(semester));
+    if let Some(carrera_id) = source.carrera_id {
+        candidates = candidates.filter(asignaturas::Column::CarreraId.eq(carrera_id));
+    }
+    let candidates: HashMap<String, asignaturas::Model> = candidates
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .into_iter()
+        .map(|s| (name_key(&s.name), s))
+        .collect();
+
+    // Asignatura de destino de cada asignatura original
+    let mut targets: HashMap<i32, i32> = HashMap::new();
+    let mut remapped = Vec::new();
+    let mut skipped = Vec::new();
+    for subject_id in &source_ids {
+        let cells = allocations.iter().filter(|a| a.asignatura_id == *subject_id).count();
+        let Some(subject) = subjects.get(subject_id) else {
+            skipped.push(SkippedSubject {
+                asignatura_id: *subject_id,
+                name: String::new(),
+                reason: "La asignatura ya no existe".to_string(),
+                cells,
+            });
+            continue;
+        };
+
+        if let Some(to_id) = input.remap.get(subject_id) {
+            let Some(to) = subjects.get(to_id) else {
+                return Err(Status::UnprocessableEntity);
+            };
+            // La asignatura indicada debe ser del año, período y carrera
+            // del balance nuevo; si no, sus horas no se copian
+            let same_career = source.carrera_id.is_none() || to.carrera_id == source.carrera_id;
+            if to.year != year || to.semester != semester || !same_career {
+                skipped.push(SkippedSubject {
+                    asignatura_id: *subject_id,
+                    name: subject.name.clone(),
+                    reason: format!(
+                        "La asignatura indicada ({}) es de {} ({}){} y no de {} ({})",
+                        to.name,
+                        to.year.as_str(),
+                        to.semester.as_str(),
+                        if same_career { "" } else { " de otra carrera" },
+                        academic_year,
+                        period
+                    ),
+                    cells,
+                });
+                continue;
+            }
+            targets.insert(*subject_id, to.id);
+            remapped.push(RemappedSubject {
+                from_id: *subject_id,
+                to_id: to.id,
+                name: to.name.clone(),
+            });
+        } else if subject.year == year && subject.semester == semester {
+            targets.insert(*subject_id, *subject_id);
+        } else if let Some(to) = candidates.get(&name_key(&subject.name)) {
+            targets.insert(*subject_id, to.id);
+            remapped.push(RemappedSubject {
+                from_id: *subject_id,
+                to_id: to.id,
+                name: to.name.clone(),
+            });
+        } else {
+            skipped.push(SkippedSubject {
+                asignatura_id: *subject_id,
+                name: subject.name.clone(),
+                reason: format!(
+                    "La asignatura es de {} ({}) y no hay una equivalente en {} ({})",
+                    subject.year.as_str(),
+                    subject.semester.as_str(),
+                    academic_year,
+                    period
+                ),
+                cells,
+            });
+        }
+    }
+
+    let now = chrono::Local::now().naive_local();
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    let balance = balances::ActiveModel {
+        user_id: Set(user.0.user_id()),
+        academic_year: Set(academic_year),
+        period: Set(period),
+        school_year: Set(input.school_year.trim().to_string()),
+        start_date: Set(input.start_date),
+        status: Set(BalanceStatus::Borrador.as_str().to_string()),
+        carrera_id: Set(source.carrera_id),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|_| Status::InternalServerError)?;
+
+    // Horas por celda; si dos asignaturas se reasignan a la misma, se suman
+    let mut cells: HashMap<(i32, i32), asignaciones_horas::Model> = HashMap::new();
+    for allocation in &allocations {
+        let Some(to_id) = targets.get(&allocation.asignatura_id) else {
+            continue;
+        };
+        cells
+            .entry((*to_id, allocation.week))
+            .and_modify(|c| {
+                c.c += allocation.c;
+                c.cp += allocation.cp;
+                c.s += allocation.s;
+                c.pl += allocation.pl;
+                c.te += allocation.te;
+                c.t += allocation.t;
+                c.pp += allocation.pp;
+            })
+            .or_insert_with(|| asignaciones_horas::Model {
+                asignatura_id: *to_id,
+                ..allocation.clone()
+            });
+    }
+    let copied_cells = cells.len();
+    if !cells.is_empty() {
+        let models = cells.into_values().map(|c| asignaciones_horas::ActiveModel {
+            balance_id: Set(balance.id),
+            asignatura_id: Set(c.asignatura_id),
+            week: Set(c.week),
+            c: Set(c.c),
+            cp: Set(c.cp),
+            s: Set(c.s),
+            pl: Set(c.pl),
+            te: Set(c.te),
+            t: Set(c.t),
+            pp: Set(c.pp),
+            ..Default::default()
+        });
+        asignaciones_horas::Entity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+    }
+
+    if !groups.is_empty() {
+        let models = groups.iter().map(|g| grupos::ActiveModel {
+            balance_id: Set(balance.id),
+            name: Set(g.name.clone()),
+            subgroups: Set(g.subgroups),
+            ..Default::default()
+        });
+        grupos::Entity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+    }
+
+    record_revision(&txn, &balance, user.0.user_id())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    // Calendario regenerado a partir de la nueva fecha de inicio
+    let non_teaching = load_non_teaching(&db.db, balance.start_date)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let weeks = generate_calendar(balance.start_date, &[], &non_teaching);
+    let scheduled_weeks: BTreeSet<i32> = allocations
+        .iter()
+        .filter(|a| targets.contains_key(&a.asignatura_id))
+        .map(|a| a.week)
+        .collect();
+    let reduced_weeks = weeks
+        .iter()
+        .filter(|w| w.reduced && scheduled_weeks.contains(&w.week))
+        .map(|w| w.week)
+        .collect();
+
+    Ok(Json(CloneResponse {
+        balance,
+        copied_cells,
+        remapped,
+        skipped,
+        reduced_weeks,
+        weeks,
+    }))
+}
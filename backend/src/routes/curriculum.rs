@@ -0,0 +1,187 @@
+use crate::*;
+use crate::routes::balances::find_visible_balance;
+use crate::sea_orm_active_enums::{AcademicYear, Semester};
+use crate::utils::curriculum::{compare_with_plan, DeviationReport};
+use crate::utils::jwt::{AuthenticatedUser, Require};
+use crate::utils::permissions::perm;
+use crate::utils::totals::TypeHours;
+use rocket::{get, put};
+use sea_orm::{Condition, QueryOrder, TransactionTrait};
+use serde::Deserialize;
+
+/// Horas oficiales de una asignatura en el plan de estudio
+#[derive(Deserialize)]
+pub struct TargetInput {
+    asignatura_id: i32,
+    #[serde(flatten)]
+    hours: TypeHours,
+}
+
+/// Busca un plan de estudio por su ID
+async fn find_plan(db: &DatabaseConnection, id: i32) -> Result<planes_de_estudio::Model, Status> {
+    planes_de_estudio::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)
+}
+
+/// Horas oficiales de cada asignatura del plan
+#[get("/api/curricula/<id>/targets")]
+pub async fn get_targets(
+    _user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<Vec<horas_plan::Model>>, Status> {
+    let plan = find_plan(&db.db, id).await?;
+
+    let list = horas_plan::Entity::find()
+        .filter(horas_plan::Column::PlanId.eq(plan.id))
+        .order_by_asc(horas_plan::Column::AsignaturaId)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Reemplaza las horas oficiales del plan - Requiere permiso de escritura
+#[put("/api/curricula/<id>/targets", format = "json", data = "<input>")]
+pub async fn save_targets(
+    _writer: Require<perm::WriteCatalog>,
+    id: i32,
+    input: Json<Vec<TargetInput>>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<horas_plan::Model>>, Status> {
+    let plan = find_plan(&db.db, id).await?;
+
+    if input.iter().any(|t| t.hours.has_negative()) {
+        return Err(Status::BadRequest);
+    }
+
+    // Una fila por asignatura, y todas deben ser de la carrera del plan
+    let mut subject_ids: Vec<i32> = input.iter().map(|t| t.asignatura_id).collect();
+    subject_ids.sort_unstable();
+    subject_ids.dedup();
+    if subject_ids.len() != input.len() {
+        return Err(Status::BadRequest);
+    }
+    let found = asignaturas::Entity::find()
+        .filter(asignaturas::Column::Id.is_in(subject_ids.clone()))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if found.len() != subject_ids.len()
+        || found.iter().any(|s| s.carrera_id != Some(plan.carrera_id))
+    {
+        return Err(Status::UnprocessableEntity);
+    }
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    horas_plan::Entity::delete_many()
+        .filter(horas_plan::Column::PlanId.eq(plan.id))
+        .exec(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let mut saved = Vec::with_capacity(input.len());
+    for target in input.iter() {
+        let model = horas_plan::ActiveModel {
+            plan_id: Set(plan.id),
+            asignatura_id: Set(target.asignatura_id),
+            c: Set(target.hours.c),
+            cp: Set(target.hours.cp),
+            s: Set(target.hours.s),
+            pl: Set(target.hours.pl),
+            te: Set(target.hours.te),
+            t: Set(target.hours.t),
+            pp: Set(target.hours.pp),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+        saved.push(model);
+    }
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(saved))
+}
+
+/// Compara lo que planifica el balance con las horas oficiales del plan,
+/// por asignatura y tipo de actividad, señalando faltas y excesos.
+///
+/// Sin `plan_id` se usa el plan vigente más reciente de la carrera del balance;
+/// el plan indicado debe ser de esa carrera.
+#[get("/api/balances/<id>/deviations?<plan_id>")]
+pub async fn plan_deviations(
+    user: AuthenticatedUser,
+    id: i32,
+    plan_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<DeviationReport>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let plan = match (plan_id, balance.carrera_id) {
+        (Some(plan_id), carrera_id) => {
+            let plan = find_plan(&db.db, plan_id).await?;
+            if Some(plan.carrera_id) != carrera_id {
+                return Err(Status::BadRequest);
+            }
+            plan
+        }
+        (None, Some(carrera_id)) => planes_de_estudio::Entity::find()
+            .filter(planes_de_estudio::Column::CarreraId.eq(carrera_id))
+            .filter(planes_de_estudio::Column::IsActive.eq(true))
+            .order_by_desc(planes_de_estudio::Column::SchoolYear)
+            .one(&db.db)
+            .await
+            .map_err(|_| Status::InternalServerError)?
+            .ok_or(Status::NotFound)?,
+        (None, None) => return Err(Status::BadRequest),
+    };
+
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let targets = horas_plan::Entity::find()
+        .filter(horas_plan::Column::PlanId.eq(plan.id))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    // Asignaturas con horas en el balance y asignaturas del plan que
+    // corresponden al año y período del balance
+    let mut condition = Condition::any().add(
+        asignaturas::Column::Id.is_in(allocations.iter().map(|a| a.asignatura_id)),
+    );
+    if let (Some(year), Some(semester)) = (
+        AcademicYear::parse(&balance.academic_year),
+        Semester::parse(&balance.period),
+    ) {
+        condition = condition.add(
+            Condition::all()
+                .add(asignaturas::Column::Id.is_in(targets.iter().map(|t| t.asignatura_id)))
+                .add(asignaturas::Column::Year.eq(year))
+                .add(asignaturas::Column::Semester.eq(semester)),
+        );
+    }
+    let subjects = asignaturas::Entity::find()
+        .filter(condition)
+        .order_by_asc(asignaturas::Column::Name)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(compare_with_plan(
+        balance.id,
+        plan.id,
+        &subjects,
+        &allocations,
+        &targets,
+    )))
+}
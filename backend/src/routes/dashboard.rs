@@ -0,0 +1,118 @@
+use crate::*;
+use crate::routes::balances::{visible_balances, PERIODS};
+use crate::sea_orm_active_enums::AcademicYear;
+use crate::utils::export::career_info;
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::permissions::Permission;
+use crate::utils::totals::TEACHING_WEEKS;
+use crate::utils::workflow::BalanceStatus;
+use rocket::get;
+use sea_orm::prelude::DateTime;
+use sea_orm::{PaginatorTrait, QueryOrder, QuerySelect};
+use serde::Serialize;
+
+/// Cantidad de balances recientes del dashboard
+const RECENT_BALANCES: u64 = 5;
+
+/// Fila de "Balances Recientes" con los enlaces de sus acciones
+#[derive(Serialize)]
+pub struct RecentBalance {
+    pub id: i32,
+    pub year_label: String,
+    pub period: String,
+    pub school_year: String,
+    pub status: String,
+    pub updated_at: Option<DateTime>,
+    pub editable: bool,
+    pub edit_url: String,
+    pub export_xlsx_url: String,
+    pub export_pdf_url: String,
+}
+
+/// Datos del dashboard (principal.html)
+#[derive(Serialize)]
+pub struct DashboardResponse {
+    pub academic_years: usize,
+    pub active_subjects: u64,
+    pub teaching_weeks: i32,
+    pub periods: usize,
+    pub balances: u64,
+    pub pending_approval: u64,
+    pub recent: Vec<RecentBalance>,
+}
+
+/// Estadísticas y balances recientes del dashboard, opcionalmente de una carrera
+#[get("/api/dashboard?<carrera_id>")]
+pub async fn dashboard(
+    user: AuthenticatedUser,
+    carrera_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<DashboardResponse>, Status> {
+    let mut subjects = asignaturas::Entity::find();
+    if let Some(carrera_id) = carrera_id {
+        subjects = subjects.filter(asignaturas::Column::CarreraId.eq(carrera_id));
+    }
+    let active_subjects = subjects
+        .clone()
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let academic_years = subjects
+        .select_only()
+        .column(asignaturas::Column::Year)
+        .distinct()
+        .into_tuple::<AcademicYear>()
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .len();
+
+    let balances = visible_balances(&user.0, carrera_id)
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let pending_approval = visible_balances(&user.0, carrera_id)
+        .filter(balances::Column::Status.eq(BalanceStatus::Enviado.as_str()))
+        .count(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let list = visible_balances(&user.0, carrera_id)
+        .order_by_desc(balances::Column::UpdatedAt)
+        .limit(RECENT_BALANCES)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let mut recent = Vec::with_capacity(list.len());
+    for balance in list {
+        let career = career_info(&db.db, balance.carrera_id)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+        let editable = (balance.user_id == user.0.user_id() || user.0.has(Permission::EditAllBalances))
+            && BalanceStatus::parse(&balance.status).is_none_or(|s| s.is_editable());
+
+        recent.push(RecentBalance {
+            id: balance.id,
+            year_label: career.year_label(&balance.academic_year),
+            period: balance.period,
+            school_year: balance.school_year,
+            status: balance.status,
+            updated_at: balance.updated_at,
+            editable,
+            edit_url: format!("/balance?id={}", balance.id),
+            export_xlsx_url: format!("/api/balances/{}/export/xlsx", balance.id),
+            export_pdf_url: format!("/api/balances/{}/export/pdf", balance.id),
+        });
+    }
+
+    Ok(Json(DashboardResponse {
+        academic_years,
+        active_subjects,
+        teaching_weeks: TEACHING_WEEKS,
+        periods: PERIODS.len(),
+        balances,
+        pending_approval,
+        recent,
+    }))
+}
@@ -0,0 +1,138 @@
+use crate::*;
+use crate::routes::balances::{find_editable_balance, find_visible_balance, lock_editable_balance};
+use crate::utils::calendar::{generate_calendar, load_non_teaching, CalendarWeek};
+use crate::utils::db::write_error;
+use crate::utils::evaluations::{check_evaluations, dates_are_valid, EvaluationReport};
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::validation::thresholds_for;
+use chrono::NaiveDate;
+use rocket::{get, put};
+use sea_orm::{QueryOrder, TransactionTrait};
+use serde::Deserialize;
+
+/// Fecha exacta de una prueba parcial
+#[derive(Deserialize)]
+pub struct EvaluationDateInput {
+    asignatura_id: i32,
+    date: NaiveDate,
+}
+
+/// Calendario real del balance
+async fn balance_weeks(
+    db: &DatabaseConnection,
+    balance: &balances::Model,
+) -> Result<Vec<CalendarWeek>, Status> {
+    let non_teaching = load_non_teaching(db, balance.start_date)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    Ok(generate_calendar(balance.start_date, &[], &non_teaching))
+}
+
+async fn evaluation_dates(
+    db: &DatabaseConnection,
+    balance_id: i32,
+) -> Result<Vec<fechas_evaluacion::Model>, Status> {
+    fechas_evaluacion::Entity::find()
+        .filter(fechas_evaluacion::Column::BalanceId.eq(balance_id))
+        .order_by_asc(fechas_evaluacion::Column::Date)
+        .order_by_asc(fechas_evaluacion::Column::AsignaturaId)
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)
+}
+
+/// Revisa las pruebas parciales del balance y calcula el informe
+async fn evaluation_report(
+    db: &DatabaseConnection,
+    balance: &balances::Model,
+    weeks: &[CalendarWeek],
+) -> Result<EvaluationReport, Status> {
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let dates = evaluation_dates(db, balance.id).await?;
+    let thresholds = thresholds_for(db, &balance.academic_year)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(check_evaluations(&allocations, &dates, weeks, &thresholds))
+}
+
+/// Pruebas parciales por semana, semanas sobrecargadas con alternativas y
+/// coincidencias de fechas
+#[get("/api/balances/<id>/evaluations")]
+pub async fn check_balance_evaluations(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<EvaluationReport>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+    let weeks = balance_weeks(&db.db, &balance).await?;
+
+    Ok(Json(evaluation_report(&db.db, &balance, &weeks).await?))
+}
+
+/// Lista las fechas exactas de las pruebas parciales del balance
+#[get("/api/balances/<id>/evaluation-dates")]
+pub async fn get_evaluation_dates(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<Vec<fechas_evaluacion::Model>>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    Ok(Json(evaluation_dates(&db.db, balance.id).await?))
+}
+
+/// Reemplaza las fechas de las pruebas parciales y devuelve el informe
+/// actualizado. Cada fecha debe ser de una asignatura con horas en el balance
+/// y caer en un día disponible de una semana lectiva.
+#[put("/api/balances/<id>/evaluation-dates", format = "json", data = "<input>")]
+pub async fn save_evaluation_dates(
+    user: AuthenticatedUser,
+    id: i32,
+    input: Json<Vec<EvaluationDateInput>>,
+    db: &State<AppState>,
+) -> Result<Json<EvaluationReport>, Status> {
+    let balance = find_editable_balance(&db.db, id, &user.0).await?;
+    let weeks = balance_weeks(&db.db, &balance).await?;
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    lock_editable_balance(&txn, balance.id).await?;
+
+    // Las fechas solo pueden ser de asignaturas con horas en el balance
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .all(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let dates: Vec<(i32, NaiveDate)> = input.iter().map(|d| (d.asignatura_id, d.date)).collect();
+    if !dates_are_valid(&dates, &allocations, &weeks) {
+        return Err(Status::BadRequest);
+    }
+
+    fechas_evaluacion::Entity::delete_many()
+        .filter(fechas_evaluacion::Column::BalanceId.eq(balance.id))
+        .exec(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    if !input.is_empty() {
+        let models = input.iter().map(|d| fechas_evaluacion::ActiveModel {
+            balance_id: Set(balance.id),
+            asignatura_id: Set(d.asignatura_id),
+            date: Set(d.date),
+            ..Default::default()
+        });
+        fechas_evaluacion::Entity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(write_error)?;
+    }
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(evaluation_report(&db.db, &balance, &weeks).await?))
+}
@@ -0,0 +1,236 @@
+use crate::*;
+use crate::routes::balances::{find_visible_balance, visible_balances};
+use crate::sea_orm_active_enums::{AcademicYear, Semester};
+use crate::utils::calendar::{generate_calendar, load_non_teaching, CalendarWeek};
+use crate::utils::excel::build_balance_xlsx;
+use crate::utils::export::{career_info, content_disposition, BalanceHeader, CareerInfo};
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::pdf::{build_balance_pdf, build_report_pdf};
+use crate::utils::totals::{
+    compute_totals, group_layout, totals_for_balance, ActivityType, BalanceTotals,
+};
+use rocket::get;
+use rocket::http::{ContentType, Header};
+use rocket::Responder;
+use sea_orm::{Condition, QueryOrder};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Archivo descargable (el navegador lo guarda con el nombre indicado)
+#[derive(Responder)]
+pub struct FileDownload {
+    body: Vec<u8>,
+    content_type: ContentType,
+    disposition: Header<'static>,
+}
+
+impl FileDownload {
+    pub fn new(body: Vec<u8>, content_type: ContentType, file_name: String) -> Self {
+        FileDownload {
+            body,
+            content_type,
+            disposition: Header::new("Content-Disposition", content_disposition(&file_name)),
+        }
+    }
+}
+
+/// Datos necesarios para exportar un balance
+struct BalanceExport {
+    header: BalanceHeader,
+    weeks: Vec<CalendarWeek>,
+    allocations: Vec<asignaciones_horas::Model>,
+    totals: BalanceTotals,
+}
+
+/// Carga el calendario, las horas y los totales de un balance
+async fn load_balance_export(
+    db: &DatabaseConnection,
+    balance: &balances::Model,
+) -> Result<BalanceExport, Status> {
+    let non_teaching = load_non_teaching(db, balance.start_date)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let weeks = generate_calendar(balance.start_date, &[], &non_teaching);
+
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    // Se incluyen todas las asignaturas del año y período del balance (de su
+    // carrera), aunque todavía no tengan horas asignadas
+    let subject_ids: Vec<i32> = allocations.iter().map(|a| a.asignatura_id).collect();
+    let mut condition = Condition::any().add(asignaturas::Column::Id.is_in(subject_ids));
+    if let (Some(year), Some(semester)) = (
+        AcademicYear::parse(&balance.academic_year),
+        Semester::parse(&balance.period),
+    ) {
+        let mut same_year = Condition::all()
+            .add(asignaturas::Column::Year.eq(year))
+            .add(asignaturas::Column::Semester.eq(semester));
+        if let Some(carrera_id) = balance.carrera_id {
+            same_year = same_year.add(asignaturas::Column::CarreraId.eq(carrera_id));
+        }
+        condition = condition.add(same_year);
+    }
+    let subjects = asignaturas::Entity::find()
+        .filter(condition)
+        .order_by_asc(asignaturas::Column::Name)
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let career = career_info(db, balance.carrera_id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let layout = group_layout(db, balance)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(BalanceExport {
+        header: BalanceHeader::for_balance(balance, &career),
+        totals: compute_totals(&subjects, &allocations, layout),
+        weeks,
+        allocations,
+    })
+}
+
+/// Nombre del usuario, para el bloque de firmas
+async fn user_name(db: &DatabaseConnection, user_id: i32) -> Result<String, Status> {
+    Ok(usuarios::Entity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .map(|u| u.name)
+        .unwrap_or_default())
+}
+
+/// Exporta un balance a .xlsx con el formato oficial ("Exportar a Excel")
+#[get("/api/balances/<id>/export/xlsx")]
+pub async fn export_xlsx(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<FileDownload, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+    let data = load_balance_export(&db.db, &balance).await?;
+
+    let body = build_balance_xlsx(&data.header, &data.weeks, &data.allocations, &data.totals)
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(FileDownload::new(
+        body,
+        ContentType::new("application", "vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        format!("{}.xlsx", data.header.file_stem()),
+    ))
+}
+
+/// Exporta un balance a PDF (documento no editable para las reuniones de aprobación)
+#[get("/api/balances/<id>/export/pdf")]
+pub async fn export_pdf(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<FileDownload, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+    let data = load_balance_export(&db.db, &balance).await?;
+    let coordinator = user_name(&db.db, balance.user_id).await?;
+
+    let body = build_balance_pdf(
+        &data.header,
+        &data.weeks,
+        &data.allocations,
+        &data.totals,
+        &coordinator,
+    )
+    .map_err(|_| Status::InternalServerError)?;
+
+    Ok(FileDownload::new(
+        body,
+        ContentType::PDF,
+        format!("{}.pdf", data.header.file_stem()),
+    ))
+}
+
+/// Reporte PDF del dashboard: totales de cada balance, filtrables por carrera,
+/// curso y período
+#[get("/api/reports/pdf?<carrera_id>&<school_year>&<period>")]
+pub async fn export_report_pdf(
+    user: AuthenticatedUser,
+    carrera_id: Option<i32>,
+    school_year: Option<String>,
+    period: Option<String>,
+    db: &State<AppState>,
+) -> Result<FileDownload, Status> {
+    let mut query = visible_balances(&user.0, carrera_id)
+        .order_by_asc(balances::Column::SchoolYear)
+        .order_by_asc(balances::Column::Period)
+        .order_by_asc(balances::Column::AcademicYear);
+    if let Some(school_year) = &school_year {
+        query = query.filter(balances::Column::SchoolYear.eq(school_year.clone()));
+    }
+    if let Some(period) = &period {
+        query = query.filter(balances::Column::Period.eq(period.clone()));
+    }
+    let list = query
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let columns: Vec<String> = ["Año", "Período", "Curso", "Total"]
+        .into_iter()
+        .chain(ActivityType::ALL.iter().map(|a| a.code()))
+        .chain(["Coef."])
+        .map(|c| c.to_string())
+        .collect();
+
+    let career = career_info(&db.db, carrera_id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let mut careers: HashMap<Option<i32>, CareerInfo> = HashMap::new();
+
+    let mut rows = Vec::with_capacity(list.len());
+    for balance in &list {
+        if let Entry::Vacant(entry) = careers.entry(balance.carrera_id) {
+            let info = career_info(&db.db, balance.carrera_id)
+                .await
+                .map_err(|_| Status::InternalServerError)?;
+            entry.insert(info);
+        }
+        let totals = totals_for_balance(&db.db, balance)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+        let mut row = vec![
+            careers[&balance.carrera_id].year_label(&balance.academic_year),
+            balance.period.clone(),
+            balance.school_year.clone(),
+            totals.total.to_string(),
+        ];
+        row.extend(ActivityType::ALL.iter().map(|a| totals.hours.get(*a).to_string()));
+        row.push(format!("{:.2}", totals.coef));
+        rows.push(row);
+    }
+
+    let info = format!(
+        "Carrera {} | Curso: {} | Período: {}",
+        if carrera_id.is_some() { career.name.as_str() } else { "Todas" },
+        school_year.as_deref().unwrap_or("Todos"),
+        period.as_deref().unwrap_or("Todos"),
+    );
+    let body = build_report_pdf(
+        &career.faculty,
+        "Reporte de Balances de Carga",
+        &info,
+        &columns,
+        &rows,
+        &user.0.name,
+    )
+    .map_err(|_| Status::InternalServerError)?;
+
+    Ok(FileDownload::new(
+        body,
+        ContentType::PDF,
+        "reporte_balances.pdf".to_string(),
+    ))
+}
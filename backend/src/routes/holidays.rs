@@ -0,0 +1,123 @@
+use crate::*;
+use crate::utils::jwt::{AuthenticatedUser, Require};
+use crate::utils::permissions::perm;
+use rocket::{get, post, put, delete};
+use sea_orm::{ModelTrait, QueryOrder};
+use sea_orm::prelude::Date;
+use serde::Deserialize;
+
+/// Tipos de período no lectivo
+pub const NON_TEACHING_KINDS: [&str; 3] = ["feriado", "vacaciones", "evento"];
+
+/// Datos de un feriado, período de vacaciones o evento institucional
+#[derive(Deserialize)]
+pub struct HolidayInput {
+    description: String,
+    kind: String,
+    start_date: Date,
+    end_date: Date,
+}
+
+impl HolidayInput {
+    fn is_valid(&self) -> bool {
+        NON_TEACHING_KINDS.contains(&self.kind.as_str())
+            && !self.description.trim().is_empty()
+            && self.start_date <= self.end_date
+    }
+}
+
+/// Lista los días no lectivos (cualquier usuario autenticado puede consultarlos)
+#[get("/api/holidays")]
+pub async fn list_holidays(
+    _user: AuthenticatedUser,
+    db: &State<AppState>,
+) -> Result<Json<Vec<dias_no_lectivos::Model>>, Status> {
+    let list = dias_no_lectivos::Entity::find()
+        .order_by_asc(dias_no_lectivos::Column::StartDate)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Registra un período no lectivo - Requiere permiso de configuración
+#[post("/api/holidays", format = "json", data = "<input>")]
+pub async fn create_holiday(
+    _admin: Require<perm::ManageSettings>,
+    input: Json<HolidayInput>,
+    db: &State<AppState>,
+) -> Result<Json<dias_no_lectivos::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let holiday = dias_no_lectivos::ActiveModel {
+        description: Set(input.description.trim().to_string()),
+        kind: Set(input.kind.clone()),
+        start_date: Set(input.start_date),
+        end_date: Set(input.end_date),
+        created_at: Set(Some(chrono::Local::now().naive_local())),
+        ..Default::default()
+    };
+
+    let saved = holiday
+        .insert(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(saved))
+}
+
+/// Modifica un período no lectivo - Requiere permiso de configuración
+#[put("/api/holidays/<id>", format = "json", data = "<input>")]
+pub async fn update_holiday(
+    _admin: Require<perm::ManageSettings>,
+    id: i32,
+    input: Json<HolidayInput>,
+    db: &State<AppState>,
+) -> Result<Json<dias_no_lectivos::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let holiday = dias_no_lectivos::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: dias_no_lectivos::ActiveModel = holiday.into();
+    active.description = Set(input.description.trim().to_string());
+    active.kind = Set(input.kind.clone());
+    active.start_date = Set(input.start_date);
+    active.end_date = Set(input.end_date);
+
+    let updated = active
+        .update(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina un período no lectivo - Requiere permiso de configuración
+#[delete("/api/holidays/<id>")]
+pub async fn delete_holiday(
+    _admin: Require<perm::ManageSettings>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let holiday = dias_no_lectivos::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    holiday
+        .delete(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
@@ -0,0 +1,253 @@
+use crate::*;
+use crate::routes::balances::{find_editable_balance, lock_editable_balance};
+use crate::sea_orm_active_enums::AcademicYear;
+use crate::utils::import::{
+    check_rows, parse_rows, read_table, subject_key, ImportError, ImportRow,
+};
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::permissions::Permission;
+use crate::utils::revisions::record_revision;
+use rocket::data::{Data, ToByteUnit};
+use rocket::post;
+use sea_orm::TransactionTrait;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Tamaño máximo del archivo de importación
+const MAX_IMPORT_SIZE_MIB: u64 = 5;
+
+/// Fila de la vista previa con la acción que se realizará sobre la asignatura
+#[derive(Serialize)]
+pub struct ImportPreviewRow {
+    #[serde(flatten)]
+    row: ImportRow,
+    action: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct ImportResponse {
+    pub dry_run: bool,
+    pub applied: bool,
+    pub rows: Vec<ImportPreviewRow>,
+    pub errors: Vec<ImportError>,
+    pub created: usize,
+    pub allocations: usize,
+}
+
+/// Importa asignaturas (y opcionalmente horas por semana de un balance)
+/// desde un .csv o .xlsx ("Importar Datos").
+///
+/// Las asignaturas se dan de alta en la carrera del balance o, si no se
+/// indica balance, en la carrera `carrera_id`. Las que ya existen no se
+/// modifican, y las horas solo se importan para asignaturas del año y
+/// semestre del balance.
+///
+/// Con `dry_run=true` (por defecto) solo devuelve la vista previa con los
+/// errores por fila; con `dry_run=false` aplica los cambios si no hay errores.
+#[post("/api/import?<balance_id>&<carrera_id>&<dry_run>", data = "<data>")]
+pub async fn import_data(
+    user: AuthenticatedUser,
+    balance_id: Option<i32>,
+    carrera_id: Option<i32>,
+    dry_run: Option<bool>,
+    data: Data<'_>,
+    db: &State<AppState>,
+) -> Result<Json<ImportResponse>, Status> {
+    let dry_run = dry_run.unwrap_or(true);
+
+    let bytes = data
+        .open(MAX_IMPORT_SIZE_MIB.mebibytes())
+        .into_bytes()
+        .await
+        .map_err(|_| Status::BadRequest)?;
+    if !bytes.is_complete() {
+        return Err(Status::PayloadTooLarge);
+    }
+
+    let mut parsed = match read_table(&bytes) {
+        Ok(table) => parse_rows(&table),
+        Err(message) => {
+            return Ok(Json(ImportResponse {
+                dry_run,
+                applied: false,
+                rows: Vec::new(),
+                errors: vec![ImportError {
+                    line: 0,
+                    field: String::new(),
+                    message,
+                }],
+                created: 0,
+                allocations: 0,
+            }));
+        }
+    };
+
+    let balance = match balance_id {
+        Some(id) => Some(find_editable_balance(&db.db, id, &user.0).await?),
+        None => None,
+    };
+    if balance.is_none() {
+        for row in parsed.rows.iter().filter(|r| r.hours.is_some()) {
+            parsed.errors.push(ImportError {
+                line: row.line,
+                field: "balance_id".to_string(),
+                message: "Para importar horas hay que indicar el balance".to_string(),
+            });
+        }
+    }
+
+    let carrera_id = balance.as_ref().and_then(|b| b.carrera_id).or(carrera_id);
+    if let Some(id) = carrera_id {
+        carreras::Entity::find_by_id(id)
+            .one(&db.db)
+            .await
+            .map_err(|_| Status::InternalServerError)?
+            .ok_or(Status::BadRequest)?;
+    }
+
+    // Asignaturas existentes de la carrera por (nombre, año)
+    let existing: HashMap<(String, AcademicYear), asignaturas::Model> = asignaturas::Entity::find()
+        .filter(match carrera_id {
+            Some(id) => asignaturas::Column::CarreraId.eq(id),
+            None => asignaturas::Column::CarreraId.is_null(),
+        })
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .into_iter()
+        .map(|s| (subject_key(&s.name, s.year), s))
+        .collect();
+
+    let catalog = existing
+        .iter()
+        .map(|(key, subject)| (key.clone(), subject.semester))
+        .collect();
+    check_rows(
+        &mut parsed,
+        &catalog,
+        balance
+            .as_ref()
+            .map(|b| (b.academic_year.as_str(), b.period.as_str())),
+    );
+
+    // Primera fila de cada asignatura del archivo
+    let mut subjects: HashMap<(String, AcademicYear), &ImportRow> = HashMap::new();
+    for row in &parsed.rows {
+        subjects.entry(subject_key(&row.name, row.year)).or_insert(row);
+    }
+
+    let action_for = |row: &ImportRow| match existing.get(&subject_key(&row.name, row.year)) {
+        None => "crear",
+        Some(_) => "sin cambios",
+    };
+    let created = subjects.values().filter(|r| action_for(r) == "crear").count();
+    let allocations = parsed.rows.iter().filter(|r| r.hours.is_some()).count();
+
+    // Dar de alta asignaturas requiere permiso de escritura sobre el
+    // catálogo; importar solo horas basta con ser dueño del balance
+    if !dry_run && created > 0 && !user.0.has(Permission::WriteCatalog) {
+        return Err(Status::Forbidden);
+    }
+
+    let applied = !dry_run && parsed.errors.is_empty();
+    if applied {
+        let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+        let balance = match &balance {
+            Some(balance) => Some(lock_editable_balance(&txn, balance.id).await?),
+            None => None,
+        };
+
+        // Alta de las asignaturas nuevas
+        let mut ids: HashMap<(String, AcademicYear), i32> = HashMap::new();
+        for (key, row) in &subjects {
+            let id = match existing.get(key) {
+                Some(subject) => subject.id,
+                None => {
+                    asignaturas::ActiveModel {
+                        name: Set(row.name.clone()),
+                        year: Set(row.year),
+                        semester: Set(row.semester),
+                        carrera_id: Set(carrera_id),
+                        ..Default::default()
+                    }
+                    .insert(&txn)
+                    .await
+                    .map_err(|_| Status::InternalServerError)?
+                    .id
+                }
+            };
+            ids.insert(key.clone(), id);
+        }
+
+        // Horas por semana del balance (reemplazan las de la misma celda)
+        if let Some(balance) = &balance {
+            for row in &parsed.rows {
+                let (Some(week), Some(hours)) = (row.week, row.hours) else {
+                    continue;
+                };
+                let asignatura_id = ids[&subject_key(&row.name, row.year)];
+
+                asignaciones_horas::Entity::delete_many()
+                    .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+                    .filter(asignaciones_horas::Column::AsignaturaId.eq(asignatura_id))
+                    .filter(asignaciones_horas::Column::Week.eq(week))
+                    .exec(&txn)
+                    .await
+                    .map_err(|_| Status::InternalServerError)?;
+
+                if hours.is_empty() {
+                    continue;
+                }
+                asignaciones_horas::ActiveModel {
+                    balance_id: Set(balance.id),
+                    asignatura_id: Set(asignatura_id),
+                    week: Set(week),
+                    c: Set(hours.c),
+                    cp: Set(hours.cp),
+                    s: Set(hours.s),
+                    pl: Set(hours.pl),
+                    te: Set(hours.te),
+                    t: Set(hours.t),
+                    pp: Set(hours.pp),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await
+                .map_err(|_| Status::InternalServerError)?;
+            }
+
+            // La importación de horas cuenta como un guardado del balance
+            if allocations > 0 {
+                let mut active: balances::ActiveModel = balance.clone().into();
+                active.updated_at = Set(Some(chrono::Local::now().naive_local()));
+                let updated = active
+                    .update(&txn)
+                    .await
+                    .map_err(|_| Status::InternalServerError)?;
+                record_revision(&txn, &updated, user.0.user_id())
+                    .await
+                    .map_err(|_| Status::InternalServerError)?;
+            }
+        }
+
+        txn.commit().await.map_err(|_| Status::InternalServerError)?;
+    }
+
+    let rows = parsed
+        .rows
+        .iter()
+        .map(|row| ImportPreviewRow {
+            action: action_for(row),
+            row: row.clone(),
+        })
+        .collect();
+
+    Ok(Json(ImportResponse {
+        dry_run,
+        applied,
+        rows,
+        errors: parsed.errors,
+        created,
+        allocations,
+    }))
+}
@@ -0,0 +1,11 @@
+use crate::utils::keys::{key_ring, JwkSet};
+use rocket::get;
+use rocket::serde::json::Json;
+
+/// Claves públicas con las que se firman los tokens (JWKS). Las claves HMAC
+/// nunca se publican, por lo que la lista está vacía si no se configura una
+/// pareja de claves RS256 o EdDSA.
+#[get("/.well-known/jwks.json")]
+pub fn jwks() -> Json<JwkSet> {
+    Json(key_ring().jwks())
+}
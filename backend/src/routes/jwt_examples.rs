@@ -6,6 +6,7 @@
 // No es necesario compilarlo, es solo para referencia
 
 use crate::utils::jwt::{Claims, create_jwt, decode_jwt, AuthenticatedUser, AdminUser};
+use crate::utils::permissions::Permission;
 use rocket::{State, http::Status};
 use rocket::serde::json::Json;
 use serde::{Serialize, Deserialize};
@@ -26,7 +27,7 @@ pub fn user_profile(user: AuthenticatedUser) -> Json<UserProfile> {
     Json(UserProfile {
         name: user.0.name.clone(),
         email: user.0.email.clone(),
-        role: if user.0.is_admin { "admin" } else { "user" }.to_string(),
+        role: if user.0.has(Permission::ManageUsers) { "admin" } else { "user" }.to_string(),
     })
 }
 
@@ -48,24 +49,24 @@ struct UserProfile {
 // ============================================================================
 
 // Token de corta duración (15 minutos) para operaciones sensibles
-pub fn create_short_lived_token(user_id: i32, email: String, name: String, is_admin: bool) -> String {
+pub fn create_short_lived_token(user_id: i32, email: String, name: String, permissions: Vec<Permission>) -> String {
     let claims = Claims::with_expiration(
         user_id,
         email,
         name,
-        is_admin,
+        permissions,
         900 // 15 minutos
     );
     create_jwt(&claims).unwrap()
 }
 
 // Token de larga duración (7 días) para "recordarme"
-pub fn create_long_lived_token(user_id: i32, email: String, name: String, is_admin: bool) -> String {
+pub fn create_long_lived_token(user_id: i32, email: String, name: String, permissions: Vec<Permission>) -> String {
     let claims = Claims::with_expiration(
         user_id,
         email,
         name,
-        is_admin,
+        permissions,
         604800 // 7 días
     );
     create_jwt(&claims).unwrap()
@@ -131,7 +132,7 @@ pub fn update_user(
     update_data: Json<UpdateUser>,
 ) -> Result<Json<&'static str>, Status> {
     // Verificar que es admin
-    if !admin.0.is_admin {
+    if !admin.0.has(Permission::ManageUsers) {
         return Err(Status::Forbidden);
     }
     
@@ -148,7 +149,7 @@ pub fn delete_user(
     admin: AdminUser, // Solo admins pueden eliminar usuarios
     user_id: i32,
 ) -> Result<Json<&'static str>, Status> {
-    if !admin.0.is_admin {
+    if !admin.0.has(Permission::ManageUsers) {
         return Err(Status::Forbidden);
     }
     
@@ -208,7 +209,7 @@ pub fn get_post(
     // let post = db.find_post(post_id)?;
     
     // Verificar si el usuario es dueño del post o es admin
-    // if post.author_id != user.0.sub && !user.0.is_admin {
+    // if post.author_id != user.0.sub && !user.0.has(Permission::ManageUsers) {
     //     return Err(Status::Forbidden);
     // }
     
@@ -251,7 +252,7 @@ pub fn refresh_token(refresh: Json<RefreshRequest>) -> Json<RefreshResponse> {
                 claims.sub.parse().unwrap_or(0),
                 claims.email,
                 claims.name,
-                claims.is_admin,
+                claims.permissions,
             );
             
             match create_jwt(&new_claims) {
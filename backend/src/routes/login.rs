@@ -1,9 +1,12 @@
 use crate::*;
-use crate::utils::jwt::{create_jwt, Claims, LoginResponse, AuthenticatedUser, AdminUser};
+use crate::utils::jwt::{access_ttl_secs, create_jwt, decode_jwt, Claims, LoginResponse, AuthenticatedUser};
+use crate::utils::permissions::permissions_for_user;
+use crate::utils::refresh::{issue_refresh_token, revoke_refresh_token, rotate_refresh_token, RefreshError};
 use rocket::{get, post, catch};
 use rocket::response::content;
 use rocket::http::{Cookie, CookieJar, SameSite};
 use rocket::time::Duration;
+use sea_orm::TransactionTrait;
 use serde::{Deserialize, Serialize};
 
 #[derive(FromForm)]
@@ -18,6 +21,17 @@ pub struct LoginJson {
     password: String,
 }
 
+#[derive(Deserialize)]
+pub struct RefreshJson {
+    refresh_token: String,
+}
+
+/// Logout de la API: opcionalmente también el token de renovación
+#[derive(Deserialize)]
+pub struct LogoutJson {
+    refresh_token: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct ApiResponse {
     message: String,
@@ -58,13 +72,11 @@ pub async fn login_form(
         return Err(Status::Unauthorized);
     }
 
-    // Crear los claims del JWT
-    let claims = Claims::new(
-        user_entity.id,
-        user_entity.email.clone(),
-        user_entity.name.clone(),
-        user_entity.isadmin.unwrap_or(false),
-    );
+    // Crear los claims del JWT con los permisos del rol
+    let permissions = permissions_for_user(&db.db, &user_entity)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let claims = Claims::from_user(&user_entity, permissions);
 
     // Generar el token JWT
     let token = create_jwt(&claims).map_err(|_| Status::InternalServerError)?;
@@ -85,7 +97,8 @@ pub async fn login_form(
     Ok(Redirect::to("/balance"))
 }
 
-/// Login con JSON (devuelve token JWT)
+/// Login con JSON: devuelve un token JWT de corta duración y un token de
+/// renovación para pedir los siguientes en `/api/refresh`
 #[post("/api/login", format = "json", data = "<credentials>")]
 pub async fn login_json(
     credentials: Json<LoginJson>,
@@ -115,21 +128,67 @@ pub async fn login_json(
         return Json(LoginResponse::error("Contraseña incorrecta".to_string()));
     }
 
-    // Crear los claims del JWT
-    let claims = Claims::new(
-        entity.id,
-        entity.email.clone(),
-        entity.name.clone(),
-        entity.isadmin.unwrap_or(false),
-    );
-
-    // Generar el token
-    match create_jwt(&claims) {
-        Ok(token) => Json(LoginResponse::success(token, &claims)),
-        Err(_) => Json(LoginResponse::error("Error al generar el token".to_string())),
+    // Crear los claims del JWT con los permisos del rol
+    let permissions = match permissions_for_user(&db.db, &entity).await {
+        Ok(permissions) => permissions,
+        Err(_) => return Json(LoginResponse::error("Error del servidor".to_string())),
+    };
+    let claims = Claims::from_user(&entity, permissions).expires_in(access_ttl_secs());
+
+    // Generar el token y el token de renovación (inicia una familia nueva)
+    let token = match create_jwt(&claims) {
+        Ok(token) => token,
+        Err(_) => return Json(LoginResponse::error("Error al generar el token".to_string())),
+    };
+    match issue_refresh_token(&db.db, entity.id, None).await {
+        Ok(refresh_token) => {
+            Json(LoginResponse::success(token, &claims).with_refresh_token(refresh_token))
+        }
+        Err(_) => Json(LoginResponse::error("Error del servidor".to_string())),
     }
 }
 
+/// Renueva el token de acceso. El token de renovación presentado se consume
+/// y se devuelve otro nuevo; reutilizar uno ya consumido revoca todos los
+/// tokens de esa sesión y obliga a iniciar sesión de nuevo.
+#[post("/api/refresh", format = "json", data = "<input>")]
+pub async fn refresh(
+    input: Json<RefreshJson>,
+    db: &State<AppState>,
+) -> Result<Json<LoginResponse>, Status> {
+    // La rotación y la emisión del token nuevo son atómicas: si algo falla
+    // antes de responder, el token presentado sigue sin usar
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    let (user_id, refresh_token) = match rotate_refresh_token(&txn, &input.refresh_token).await {
+        Ok(rotated) => rotated,
+        // La revocación de la familia debe persistir aunque la petición falle
+        Err(RefreshError::Reused) => {
+            txn.commit().await.map_err(|_| Status::InternalServerError)?;
+            return Err(Status::Unauthorized);
+        }
+        Err(RefreshError::Invalid | RefreshError::Expired) => return Err(Status::Unauthorized),
+        Err(RefreshError::Db(_)) => return Err(Status::InternalServerError),
+    };
+
+    // Los permisos se leen de nuevo: los cambios de rol se aplican al renovar
+    let user = usuarios::Entity::find_by_id(user_id)
+        .one(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::Unauthorized)?;
+    let permissions = permissions_for_user(&txn, &user)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let claims = Claims::from_user(&user, permissions).expires_in(access_ttl_secs());
+
+    let token = create_jwt(&claims).map_err(|_| Status::InternalServerError)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(LoginResponse::success(token, &claims).with_refresh_token(refresh_token)))
+}
+
 /// Página de balance - Solo usuarios autenticados
 #[get("/balance")]
 pub async fn balance_page(_user: AuthenticatedUser) -> Option<NamedFile> {
@@ -138,9 +197,14 @@ pub async fn balance_page(_user: AuthenticatedUser) -> Option<NamedFile> {
     NamedFile::open("../frontend/balance.html").await.ok()
 }
 
-/// Logout - Elimina la cookie JWT y redirecciona al login
+/// Logout - Revoca el token de la cookie, la elimina y redirecciona al login
 #[get("/logout")]
-pub fn logout(cookies: &CookieJar<'_>) -> Redirect {
+pub async fn logout(cookies: &CookieJar<'_>, db: &State<AppState>) -> Redirect {
+    // Revocar el token para que no sirva aunque alguien lo haya copiado
+    if let Some(claims) = cookies.get("jwt_token").and_then(|c| decode_jwt(c.value()).ok()) {
+        let _ = db.revoked.revoke(&db.db, &claims).await;
+    }
+
     // Eliminar la cookie JWT
     cookies.remove(Cookie::build("jwt_token"));
     
@@ -148,6 +212,28 @@ pub fn logout(cookies: &CookieJar<'_>) -> Redirect {
     Redirect::to("/login")
 }
 
+/// Logout de la API - Revoca el token presentado y, si se envía, la sesión
+/// del token de renovación
+#[post("/api/logout", data = "<input>")]
+pub async fn api_logout(
+    user: AuthenticatedUser,
+    input: Option<Json<LogoutJson>>,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    db.revoked
+        .revoke(&db.db, &user.0)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    if let Some(refresh_token) = input.and_then(|i| i.into_inner().refresh_token) {
+        revoke_refresh_token(&db.db, user.0.user_id(), &refresh_token)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+    }
+
+    Ok(Status::NoContent)
+}
+
 /// Catcher para error 401 (No autorizado) - Muestra alerta y redirige
 #[catch(401)]
 pub fn unauthorized() -> content::RawHtml<&'static str> {
@@ -1 +1,19 @@
-pub mod login;
\ No newline at end of file
+pub mod login;
+pub mod balances;
+pub mod calendar;
+pub mod careers;
+pub mod clone;
+pub mod curriculum;
+pub mod dashboard;
+pub mod evaluations;
+pub mod export;
+pub mod holidays;
+pub mod import;
+pub mod jwks;
+pub mod professors;
+pub mod reports;
+pub mod revisions;
+pub mod roles;
+pub mod subjects;
+pub mod validation;
+pub mod workflow;
\ No newline at end of file
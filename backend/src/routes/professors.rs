@@ -0,0 +1,344 @@
+use crate::*;
+use crate::utils::db::write_error;
+use crate::utils::jwt::{AuthenticatedUser, Require};
+use crate::utils::permissions::{perm, Permission};
+use crate::utils::totals::ActivityType;
+use crate::utils::workload::{professor_loads, ProfessorLoad};
+use rocket::{get, post, put, delete};
+use sea_orm::{ModelTrait, QueryOrder};
+use serde::Deserialize;
+
+/// Carga semanal máxima por defecto de un profesor
+const DEFAULT_MAX_WEEKLY_HOURS: i32 = 16;
+
+/// Datos de un profesor
+#[derive(Deserialize)]
+pub struct ProfessorInput {
+    name: String,
+    email: Option<String>,
+    usuario_id: Option<i32>,
+    max_weekly_hours: Option<i32>,
+}
+
+impl ProfessorInput {
+    fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty() && self.max_weekly_hours.is_none_or(|h| h > 0)
+    }
+
+    fn email(&self) -> Option<String> {
+        self.email
+            .as_ref()
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+    }
+}
+
+/// Asignación de un profesor a una actividad de una asignatura para un grupo
+#[derive(Deserialize)]
+pub struct AssignmentInput {
+    profesor_id: i32,
+    asignatura_id: i32,
+    activity: String,
+    group_name: String,
+}
+
+/// Nombres de los grupos del año de una asignatura: los del año académico
+/// de su carrera y los propios de los balances de ese año
+async fn year_groups(
+    db: &DatabaseConnection,
+    subject: &asignaturas::Model,
+) -> Result<Vec<String>, Status> {
+    let academic_year = subject.year.as_str();
+    let mut names: Vec<String> = match subject.carrera_id {
+        Some(carrera_id) => grupos_anio::Entity::find()
+            .filter(grupos_anio::Column::CarreraId.eq(carrera_id))
+            .filter(grupos_anio::Column::AcademicYear.eq(academic_year))
+            .all(db)
+            .await
+            .map_err(|_| Status::InternalServerError)?
+            .into_iter()
+            .map(|g| g.name)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let own = grupos::Entity::find()
+        .inner_join(balances::Entity)
+        .filter(match subject.carrera_id {
+            Some(id) => balances::Column::CarreraId.eq(id),
+            None => balances::Column::CarreraId.is_null(),
+        })
+        .filter(balances::Column::AcademicYear.eq(academic_year))
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    names.extend(own.into_iter().map(|g| g.name));
+
+    Ok(names)
+}
+
+// ========================================
+// PROFESORES
+// ========================================
+
+/// Lista los profesores
+#[get("/api/professors")]
+pub async fn list_professors(
+    _user: AuthenticatedUser,
+    db: &State<AppState>,
+) -> Result<Json<Vec<profesores::Model>>, Status> {
+    let list = profesores::Entity::find()
+        .order_by_asc(profesores::Column::Name)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Registra un profesor - Requiere permiso de gestión de profesores
+#[post("/api/professors", format = "json", data = "<input>")]
+pub async fn create_professor(
+    _admin: Require<perm::ManageProfessors>,
+    input: Json<ProfessorInput>,
+    db: &State<AppState>,
+) -> Result<Json<profesores::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let saved = profesores::ActiveModel {
+        name: Set(input.name.trim().to_string()),
+        email: Set(input.email()),
+        usuario_id: Set(input.usuario_id),
+        max_weekly_hours: Set(input.max_weekly_hours.unwrap_or(DEFAULT_MAX_WEEKLY_HOURS)),
+        created_at: Set(Some(chrono::Local::now().naive_local())),
+        ..Default::default()
+    }
+    .insert(&db.db)
+    .await
+    .map_err(write_error)?;
+
+    Ok(Json(saved))
+}
+
+/// Modifica un profesor - Requiere permiso de gestión de profesores
+#[put("/api/professors/<id>", format = "json", data = "<input>")]
+pub async fn update_professor(
+    _admin: Require<perm::ManageProfessors>,
+    id: i32,
+    input: Json<ProfessorInput>,
+    db: &State<AppState>,
+) -> Result<Json<profesores::Model>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let professor = profesores::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: profesores::ActiveModel = professor.into();
+    active.name = Set(input.name.trim().to_string());
+    active.email = Set(input.email());
+    active.usuario_id = Set(input.usuario_id);
+    if let Some(hours) = input.max_weekly_hours {
+        active.max_weekly_hours = Set(hours);
+    }
+    let updated = active.update(&db.db).await.map_err(write_error)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina un profesor y sus asignaciones - Requiere permiso de gestión de profesores
+#[delete("/api/professors/<id>")]
+pub async fn delete_professor(
+    _admin: Require<perm::ManageProfessors>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let professor = profesores::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    professor
+        .delete(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
+
+// ========================================
+// ASIGNACIONES PROFESOR - ASIGNATURA
+// ========================================
+
+/// Lista las asignaciones, filtrables por profesor o asignatura
+#[get("/api/assignments?<profesor_id>&<asignatura_id>")]
+pub async fn list_assignments(
+    _user: AuthenticatedUser,
+    profesor_id: Option<i32>,
+    asignatura_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<asignaciones_profesores::Model>>, Status> {
+    let mut query = asignaciones_profesores::Entity::find()
+        .order_by_asc(asignaciones_profesores::Column::AsignaturaId)
+        .order_by_asc(asignaciones_profesores::Column::GroupName);
+    if let Some(profesor_id) = profesor_id {
+        query = query.filter(asignaciones_profesores::Column::ProfesorId.eq(profesor_id));
+    }
+    if let Some(asignatura_id) = asignatura_id {
+        query = query.filter(asignaciones_profesores::Column::AsignaturaId.eq(asignatura_id));
+    }
+
+    let list = query
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Asigna un profesor a una actividad de una asignatura para un grupo del
+/// año de la asignatura. Si la actividad ya tenía profesor para ese grupo,
+/// se reemplaza - Requiere permiso de gestión de profesores
+#[post("/api/assignments", format = "json", data = "<input>")]
+pub async fn create_assignment(
+    _admin: Require<perm::ManageProfessors>,
+    input: Json<AssignmentInput>,
+    db: &State<AppState>,
+) -> Result<Json<asignaciones_profesores::Model>, Status> {
+    let activity = ActivityType::parse(&input.activity).ok_or(Status::BadRequest)?;
+    let group_name = input.group_name.trim().to_uppercase();
+    if group_name.is_empty() {
+        return Err(Status::BadRequest);
+    }
+
+    let subject = asignaturas::Entity::find_by_id(input.asignatura_id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::UnprocessableEntity)?;
+    if !year_groups(&db.db, &subject).await?.contains(&group_name) {
+        return Err(Status::UnprocessableEntity);
+    }
+
+    let existing = asignaciones_profesores::Entity::find()
+        .filter(asignaciones_profesores::Column::AsignaturaId.eq(input.asignatura_id))
+        .filter(asignaciones_profesores::Column::Activity.eq(activity.code()))
+        .filter(asignaciones_profesores::Column::GroupName.eq(group_name.clone()))
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let saved = match existing {
+        Some(assignment) => {
+            let mut active: asignaciones_profesores::ActiveModel = assignment.into();
+            active.profesor_id = Set(input.profesor_id);
+            active.update(&db.db).await.map_err(write_error)?
+        }
+        None => asignaciones_profesores::ActiveModel {
+            profesor_id: Set(input.profesor_id),
+            asignatura_id: Set(input.asignatura_id),
+            activity: Set(activity.code().to_string()),
+            group_name: Set(group_name),
+            ..Default::default()
+        }
+        .insert(&db.db)
+        .await
+        .map_err(write_error)?,
+    };
+
+    Ok(Json(saved))
+}
+
+/// Elimina una asignación - Requiere permiso de gestión de profesores
+#[delete("/api/assignments/<id>")]
+pub async fn delete_assignment(
+    _admin: Require<perm::ManageProfessors>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let assignment = asignaciones_profesores::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    assignment
+        .delete(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
+
+// ========================================
+// CARGA POR PROFESOR
+// ========================================
+
+/// Carga semanal de todos los profesores en un curso y período.
+///
+/// Con `week` solo se devuelve esa semana; con `overloaded=true` solo los
+/// profesores que superan su máximo (p. ej. "quién está sobrecargado en la
+/// semana 7").
+#[get("/api/professors/load?<school_year>&<period>&<week>&<overloaded>")]
+pub async fn professors_load(
+    _approver: Require<perm::ViewProfessorLoad>,
+    school_year: String,
+    period: String,
+    week: Option<i32>,
+    overloaded: Option<bool>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<ProfessorLoad>>, Status> {
+    let mut loads = professor_loads(&db.db, &school_year, &period, None)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    if let Some(week) = week {
+        for load in &mut loads {
+            load.weeks.retain(|w| w.week == week);
+            load.overloaded_weeks.retain(|w| *w == week);
+        }
+    }
+    if overloaded.unwrap_or(false) {
+        loads.retain(|l| !l.overloaded_weeks.is_empty());
+    }
+
+    Ok(Json(loads))
+}
+
+/// Carga semanal de un profesor. La puede consultar el propio profesor
+/// (si tiene cuenta vinculada), los aprobadores y los administradores.
+#[get("/api/professors/<id>/load?<school_year>&<period>")]
+pub async fn professor_load(
+    user: AuthenticatedUser,
+    id: i32,
+    school_year: String,
+    period: String,
+    db: &State<AppState>,
+) -> Result<Json<ProfessorLoad>, Status> {
+    let professor = profesores::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    if !user.0.has(Permission::ViewProfessorLoad)
+        && professor.usuario_id != Some(user.0.user_id())
+    {
+        return Err(Status::Forbidden);
+    }
+
+    let load = professor_loads(&db.db, &school_year, &period, Some(professor.id))
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .pop()
+        .ok_or(Status::NotFound)?;
+
+    Ok(Json(load))
+}
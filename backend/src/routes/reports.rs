@@ -0,0 +1,89 @@
+use crate::*;
+use crate::routes::balances::{find_visible_balance, visible_balances};
+use crate::utils::comparison::{compare_balances, ComparisonReport};
+use crate::utils::export::{career_info, CareerInfo};
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::totals::totals_for_balance;
+use rocket::get;
+use sea_orm::QueryOrder;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Máximo de balances en una comparación
+const MAX_COMPARED: usize = 12;
+
+/// Compara dos o más balances: curvas de carga semanal, totales por tipo y
+/// asignaturas que cambian.
+///
+/// Los balances se indican con `ids` (p. ej. `ids=1&ids=5`, sin repetir) o mediante
+/// filtros: `academic_year=2do` compara el mismo año a lo largo de los cursos
+/// y `school_year=2025-2026&period=1ero` compara 1ro-4to en un período.
+#[get("/api/reports/compare?<ids>&<carrera_id>&<academic_year>&<school_year>&<period>")]
+pub async fn compare_report(
+    user: AuthenticatedUser,
+    ids: Vec<i32>,
+    carrera_id: Option<i32>,
+    academic_year: Option<String>,
+    school_year: Option<String>,
+    period: Option<String>,
+    db: &State<AppState>,
+) -> Result<Json<ComparisonReport>, Status> {
+    let list = if !ids.is_empty() {
+        // Un balance repetido se compararía consigo mismo
+        let mut unique = ids.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if unique.len() != ids.len() {
+            return Err(Status::BadRequest);
+        }
+
+        let mut list = Vec::with_capacity(ids.len());
+        for id in ids {
+            list.push(find_visible_balance(&db.db, id, &user.0).await?);
+        }
+        list
+    } else {
+        if academic_year.is_none() && school_year.is_none() {
+            return Err(Status::BadRequest);
+        }
+        let mut query = visible_balances(&user.0, carrera_id)
+            .order_by_asc(balances::Column::SchoolYear)
+            .order_by_asc(balances::Column::Period)
+            .order_by_asc(balances::Column::AcademicYear);
+        if let Some(academic_year) = academic_year {
+            query = query.filter(balances::Column::AcademicYear.eq(academic_year));
+        }
+        if let Some(school_year) = school_year {
+            query = query.filter(balances::Column::SchoolYear.eq(school_year));
+        }
+        if let Some(period) = period {
+            query = query.filter(balances::Column::Period.eq(period));
+        }
+        query
+            .all(&db.db)
+            .await
+            .map_err(|_| Status::InternalServerError)?
+    };
+
+    if list.len() < 2 || list.len() > MAX_COMPARED {
+        return Err(Status::BadRequest);
+    }
+
+    let mut careers: HashMap<Option<i32>, CareerInfo> = HashMap::new();
+    let mut entries = Vec::with_capacity(list.len());
+    for balance in list {
+        if let Entry::Vacant(entry) = careers.entry(balance.carrera_id) {
+            let info = career_info(&db.db, balance.carrera_id)
+                .await
+                .map_err(|_| Status::InternalServerError)?;
+            entry.insert(info);
+        }
+        let year_label = careers[&balance.carrera_id].year_label(&balance.academic_year);
+        let totals = totals_for_balance(&db.db, &balance)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+        entries.push((balance, year_label, totals));
+    }
+
+    Ok(Json(compare_balances(&entries)))
+}
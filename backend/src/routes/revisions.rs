@@ -0,0 +1,195 @@
+use crate::*;
+use crate::routes::balances::{
+    find_editable_balance, find_visible_balance, lock_editable_balance, replace_allocations,
+    replace_groups,
+};
+use crate::utils::jwt::AuthenticatedUser;
+use crate::utils::revisions::{diff_snapshots, record_revision, BalanceSnapshot, RevisionDiff};
+use rocket::{get, post};
+use sea_orm::prelude::DateTime;
+use sea_orm::{QueryOrder, TransactionTrait};
+use serde::Serialize;
+
+/// Revisión sin la copia completa (para el listado)
+#[derive(Serialize)]
+pub struct RevisionSummary {
+    pub revision: i32,
+    pub user_id: i32,
+    pub user_name: String,
+    pub created_at: DateTime,
+    pub cells: usize,
+}
+
+/// Revisión con la copia completa del balance
+#[derive(Serialize)]
+pub struct RevisionDetail {
+    pub revision: i32,
+    pub user_id: i32,
+    pub created_at: DateTime,
+    pub snapshot: BalanceSnapshot,
+}
+
+#[derive(Serialize)]
+pub struct RestoreResponse {
+    pub restored_from: i32,
+    pub revision: i32,
+    /// Celdas no restauradas porque su asignatura ya no existe
+    pub skipped_cells: usize,
+}
+
+/// Busca una revisión concreta de un balance
+async fn find_revision(
+    db: &DatabaseConnection,
+    balance_id: i32,
+    revision: i32,
+) -> Result<balance_revisiones::Model, Status> {
+    balance_revisiones::Entity::find()
+        .filter(balance_revisiones::Column::BalanceId.eq(balance_id))
+        .filter(balance_revisiones::Column::Revision.eq(revision))
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)
+}
+
+fn snapshot_of(revision: &balance_revisiones::Model) -> Result<BalanceSnapshot, Status> {
+    BalanceSnapshot::from_revision(revision).map_err(|_| Status::InternalServerError)
+}
+
+/// Historial de revisiones de un balance (la más reciente primero)
+#[get("/api/balances/<id>/revisions")]
+pub async fn list_revisions(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<Vec<RevisionSummary>>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let revisions = balance_revisiones::Entity::find()
+        .filter(balance_revisiones::Column::BalanceId.eq(balance.id))
+        .order_by_desc(balance_revisiones::Column::Revision)
+        .find_also_related(usuarios::Entity)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let list = revisions
+        .into_iter()
+        .map(|(rev, author)| {
+            let cells = snapshot_of(&rev)?.allocations.len();
+            Ok(RevisionSummary {
+                revision: rev.revision,
+                user_id: rev.user_id,
+                user_name: author.map(|u| u.name).unwrap_or_default(),
+                created_at: rev.created_at,
+                cells,
+            })
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    Ok(Json(list))
+}
+
+/// Muestra una revisión con la copia completa del balance
+#[get("/api/balances/<id>/revisions/<revision>")]
+pub async fn get_revision(
+    user: AuthenticatedUser,
+    id: i32,
+    revision: i32,
+    db: &State<AppState>,
+) -> Result<Json<RevisionDetail>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+    let rev = find_revision(&db.db, balance.id, revision).await?;
+
+    Ok(Json(RevisionDetail {
+        revision: rev.revision,
+        user_id: rev.user_id,
+        created_at: rev.created_at,
+        snapshot: snapshot_of(&rev)?,
+    }))
+}
+
+/// Compara dos revisiones celda a celda
+#[get("/api/balances/<id>/diff?<from>&<to>")]
+pub async fn diff_revisions(
+    user: AuthenticatedUser,
+    id: i32,
+    from: i32,
+    to: i32,
+    db: &State<AppState>,
+) -> Result<Json<RevisionDiff>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+    let before = find_revision(&db.db, balance.id, from).await?;
+    let after = find_revision(&db.db, balance.id, to).await?;
+
+    Ok(Json(diff_snapshots(
+        before.revision,
+        &snapshot_of(&before)?,
+        after.revision,
+        &snapshot_of(&after)?,
+    )))
+}
+
+/// Restaura una revisión anterior. La restauración crea a su vez una nueva
+/// revisión, por lo que el historial nunca se pierde.
+#[post("/api/balances/<id>/revisions/<revision>/restore")]
+pub async fn restore_revision(
+    user: AuthenticatedUser,
+    id: i32,
+    revision: i32,
+    db: &State<AppState>,
+) -> Result<Json<RestoreResponse>, Status> {
+    let balance = find_editable_balance(&db.db, id, &user.0).await?;
+    let rev = find_revision(&db.db, balance.id, revision).await?;
+    let snapshot = snapshot_of(&rev)?;
+
+    // Se descartan las celdas de asignaturas eliminadas desde entonces
+    let subject_ids: Vec<i32> = snapshot.allocations.iter().map(|c| c.asignatura_id).collect();
+    let existing: Vec<i32> = asignaturas::Entity::find()
+        .filter(asignaturas::Column::Id.is_in(subject_ids))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    let (cells, skipped): (Vec<_>, Vec<_>) = snapshot
+        .allocations
+        .into_iter()
+        .partition(|c| existing.contains(&c.asignatura_id));
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+    let balance = lock_editable_balance(&txn, balance.id).await?;
+
+    replace_allocations(&txn, balance.id, &cells)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if let Some(groups) = &snapshot.groups {
+        replace_groups(&txn, balance.id, groups)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+    }
+
+    let mut active: balances::ActiveModel = balance.into();
+    active.academic_year = Set(snapshot.academic_year);
+    active.period = Set(snapshot.period);
+    active.school_year = Set(snapshot.school_year);
+    active.start_date = Set(snapshot.start_date);
+    active.updated_at = Set(Some(chrono::Local::now().naive_local()));
+    let updated = active
+        .update(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let new_revision = record_revision(&txn, &updated, user.0.user_id())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(RestoreResponse {
+        restored_from: rev.revision,
+        revision: new_revision.revision,
+        skipped_cells: skipped.len(),
+    }))
+}
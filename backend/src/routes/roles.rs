@@ -0,0 +1,248 @@
+use crate::*;
+use crate::utils::db::write_error;
+use crate::utils::jwt::Require;
+use crate::utils::permissions::{perm, Permission};
+use rocket::{get, post, put, delete};
+use sea_orm::{ConnectionTrait, DbErr, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Datos de un rol y los permisos que concede
+#[derive(Deserialize)]
+pub struct RoleInput {
+    code: String,
+    name: String,
+    #[serde(default)]
+    permissions: Vec<Permission>,
+}
+
+impl RoleInput {
+    fn is_valid(&self) -> bool {
+        let code = self.code.trim();
+        !code.is_empty()
+            && code.len() <= 30
+            && !code.contains(' ')
+            && !self.name.trim().is_empty()
+    }
+}
+
+/// Rol asignado a un usuario (None = sin rol)
+#[derive(Deserialize)]
+pub struct UserRoleInput {
+    rol_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct RoleWithPermissions {
+    #[serde(flatten)]
+    pub role: roles::Model,
+    pub permissions: Vec<Permission>,
+}
+
+/// Usuario con su rol (sin la contraseña)
+#[derive(Serialize)]
+pub struct UserRole {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub rol_id: Option<i32>,
+}
+
+async fn find_role(db: &DatabaseConnection, id: i32) -> Result<roles::Model, Status> {
+    roles::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)
+}
+
+/// Reemplaza los permisos concedidos por un rol
+async fn replace_permissions<C: ConnectionTrait>(
+    db: &C,
+    rol_id: i32,
+    permissions: &[Permission],
+) -> Result<(), DbErr> {
+    roles_permisos::Entity::delete_many()
+        .filter(roles_permisos::Column::RolId.eq(rol_id))
+        .exec(db)
+        .await?;
+
+    let mut codes: Vec<&str> = permissions.iter().map(|p| p.code()).collect();
+    codes.sort();
+    codes.dedup();
+    if !codes.is_empty() {
+        let models = codes.into_iter().map(|code| roles_permisos::ActiveModel {
+            rol_id: Set(rol_id),
+            permiso_code: Set(code.to_string()),
+        });
+        roles_permisos::Entity::insert_many(models).exec(db).await?;
+    }
+    Ok(())
+}
+
+async fn role_permissions(db: &DatabaseConnection, rol_id: i32) -> Result<Vec<Permission>, Status> {
+    Ok(roles_permisos::Entity::find()
+        .filter(roles_permisos::Column::RolId.eq(rol_id))
+        .all(db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .iter()
+        .filter_map(|g| Permission::parse(&g.permiso_code))
+        .collect())
+}
+
+/// Lista los permisos que pueden concederse
+#[get("/api/permissions")]
+pub async fn list_permissions(
+    _admin: Require<perm::ManageUsers>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<permisos::Model>>, Status> {
+    let list = permisos::Entity::find()
+        .order_by_asc(permisos::Column::Code)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Lista los roles con sus permisos
+#[get("/api/roles")]
+pub async fn list_roles(
+    _admin: Require<perm::ManageUsers>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<RoleWithPermissions>>, Status> {
+    let list = roles::Entity::find()
+        .order_by_asc(roles::Column::Name)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let mut granted: HashMap<i32, Vec<Permission>> = HashMap::new();
+    for g in roles_permisos::Entity::find()
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+    {
+        if let Some(permission) = Permission::parse(&g.permiso_code) {
+            granted.entry(g.rol_id).or_default().push(permission);
+        }
+    }
+
+    let list = list
+        .into_iter()
+        .map(|role| RoleWithPermissions {
+            permissions: granted.remove(&role.id).unwrap_or_default(),
+            role,
+        })
+        .collect();
+
+    Ok(Json(list))
+}
+
+/// Crea un rol con sus permisos
+#[post("/api/roles", format = "json", data = "<input>")]
+pub async fn create_role(
+    _admin: Require<perm::ManageUsers>,
+    input: Json<RoleInput>,
+    db: &State<AppState>,
+) -> Result<Json<RoleWithPermissions>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    let role = roles::ActiveModel {
+        code: Set(input.code.trim().to_lowercase()),
+        name: Set(input.name.trim().to_string()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(write_error)?;
+
+    replace_permissions(&txn, role.id, &input.permissions)
+        .await
+        .map_err(write_error)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    let permissions = role_permissions(&db.db, role.id).await?;
+    Ok(Json(RoleWithPermissions { role, permissions }))
+}
+
+/// Modifica un rol y reemplaza sus permisos. Los usuarios afectados reciben
+/// los nuevos permisos al volver a iniciar sesión.
+#[put("/api/roles/<id>", format = "json", data = "<input>")]
+pub async fn update_role(
+    _admin: Require<perm::ManageUsers>,
+    id: i32,
+    input: Json<RoleInput>,
+    db: &State<AppState>,
+) -> Result<Json<RoleWithPermissions>, Status> {
+    if !input.is_valid() {
+        return Err(Status::BadRequest);
+    }
+
+    let role = find_role(&db.db, id).await?;
+
+    let txn = db.db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    let mut active: roles::ActiveModel = role.into();
+    active.code = Set(input.code.trim().to_lowercase());
+    active.name = Set(input.name.trim().to_string());
+    let role = active.update(&txn).await.map_err(write_error)?;
+
+    replace_permissions(&txn, role.id, &input.permissions)
+        .await
+        .map_err(write_error)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    let permissions = role_permissions(&db.db, role.id).await?;
+    Ok(Json(RoleWithPermissions { role, permissions }))
+}
+
+/// Elimina un rol; sus usuarios quedan sin rol
+#[delete("/api/roles/<id>")]
+pub async fn delete_role(
+    _admin: Require<perm::ManageUsers>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let role = find_role(&db.db, id).await?;
+
+    roles::Entity::delete_by_id(role.id)
+        .exec(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Status::NoContent)
+}
+
+/// Asigna (o quita) el rol de un usuario
+#[put("/api/users/<id>/role", format = "json", data = "<input>")]
+pub async fn set_user_role(
+    _admin: Require<perm::ManageUsers>,
+    id: i32,
+    input: Json<UserRoleInput>,
+    db: &State<AppState>,
+) -> Result<Json<UserRole>, Status> {
+    let user = usuarios::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: usuarios::ActiveModel = user.into();
+    active.rol_id = Set(input.rol_id);
+    let user = active.update(&db.db).await.map_err(write_error)?;
+
+    Ok(Json(UserRole {
+        id: user.id,
+        name: user.name,
+        email: user.email,
+        rol_id: user.rol_id,
+    }))
+}
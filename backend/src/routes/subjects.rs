@@ -0,0 +1,147 @@
+use crate::*;
+use crate::sea_orm_active_enums::{AcademicYear, Semester};
+use crate::utils::db::{delete_error, write_error};
+use crate::utils::jwt::{AuthenticatedUser, Require};
+use crate::utils::permissions::perm;
+use rocket::{get, post, put, delete};
+use sea_orm::{ModelTrait, QueryOrder};
+use serde::Deserialize;
+
+/// Datos de una asignatura ("Gestión de Asignaturas").
+/// El año admite "1ro" o "1ro.ICS".
+#[derive(Deserialize)]
+pub struct SubjectInput {
+    name: String,
+    year: String,
+    semester: String,
+    carrera_id: Option<i32>,
+}
+
+impl SubjectInput {
+    /// Año y semestre normalizados, o `None` si algún dato no es válido
+    fn parse(&self) -> Option<(AcademicYear, Semester)> {
+        if self.name.trim().is_empty() || self.name.trim().len() > 100 {
+            return None;
+        }
+        Some((AcademicYear::parse(&self.year)?, Semester::parse(&self.semester)?))
+    }
+}
+
+/// Lista el catálogo de asignaturas, filtrable por año, semestre y carrera
+#[get("/api/asignaturas?<year>&<semester>&<carrera_id>")]
+pub async fn list_subjects(
+    _user: AuthenticatedUser,
+    year: Option<String>,
+    semester: Option<String>,
+    carrera_id: Option<i32>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<asignaturas::Model>>, Status> {
+    let mut query = asignaturas::Entity::find()
+        .order_by_asc(asignaturas::Column::Year)
+        .order_by_asc(asignaturas::Column::Semester)
+        .order_by_asc(asignaturas::Column::Name);
+    if let Some(year) = year {
+        let year = AcademicYear::parse(&year).ok_or(Status::BadRequest)?;
+        query = query.filter(asignaturas::Column::Year.eq(year));
+    }
+    if let Some(semester) = semester {
+        let semester = Semester::parse(&semester).ok_or(Status::BadRequest)?;
+        query = query.filter(asignaturas::Column::Semester.eq(semester));
+    }
+    if let Some(carrera_id) = carrera_id {
+        query = query.filter(asignaturas::Column::CarreraId.eq(carrera_id));
+    }
+
+    let list = query
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Carga una asignatura
+#[get("/api/asignaturas/<id>")]
+pub async fn get_subject(
+    _user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<asignaturas::Model>, Status> {
+    let subject = asignaturas::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    Ok(Json(subject))
+}
+
+/// Registra una asignatura - Requiere permiso de escritura
+#[post("/api/asignaturas", format = "json", data = "<input>")]
+pub async fn create_subject(
+    _writer: Require<perm::WriteCatalog>,
+    input: Json<SubjectInput>,
+    db: &State<AppState>,
+) -> Result<Json<asignaturas::Model>, Status> {
+    let (year, semester) = input.parse().ok_or(Status::BadRequest)?;
+
+    let saved = asignaturas::ActiveModel {
+        name: Set(input.name.trim().to_string()),
+        year: Set(year),
+        semester: Set(semester),
+        carrera_id: Set(input.carrera_id),
+        ..Default::default()
+    }
+    .insert(&db.db)
+    .await
+    .map_err(write_error)?;
+
+    Ok(Json(saved))
+}
+
+/// Modifica una asignatura - Requiere permiso de escritura
+#[put("/api/asignaturas/<id>", format = "json", data = "<input>")]
+pub async fn update_subject(
+    _writer: Require<perm::WriteCatalog>,
+    id: i32,
+    input: Json<SubjectInput>,
+    db: &State<AppState>,
+) -> Result<Json<asignaturas::Model>, Status> {
+    let (year, semester) = input.parse().ok_or(Status::BadRequest)?;
+
+    let subject = asignaturas::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    let mut active: asignaturas::ActiveModel = subject.into();
+    active.name = Set(input.name.trim().to_string());
+    active.year = Set(year);
+    active.semester = Set(semester);
+    active.carrera_id = Set(input.carrera_id);
+    let updated = active.update(&db.db).await.map_err(write_error)?;
+
+    Ok(Json(updated))
+}
+
+/// Elimina una asignatura que no tenga horas en ningún balance
+/// - Requiere permiso de escritura
+#[delete("/api/asignaturas/<id>")]
+pub async fn delete_subject(
+    _writer: Require<perm::WriteCatalog>,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Status, Status> {
+    let subject = asignaturas::Entity::find_by_id(id)
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    // La clave foránea de las horas es RESTRICT: si la asignatura tiene
+    // horas en algún balance, el borrado falla y se responde 409
+    subject.delete(&db.db).await.map_err(delete_error)?;
+
+    Ok(Status::NoContent)
+}
@@ -0,0 +1,110 @@
+use crate::*;
+use crate::routes::balances::{find_visible_balance, ACADEMIC_YEARS};
+use crate::utils::calendar::{generate_calendar, load_non_teaching};
+use crate::utils::jwt::{AuthenticatedUser, Require};
+use crate::utils::permissions::perm;
+use crate::utils::validation::{thresholds_for, validate_balance, Thresholds, ValidationReport};
+use rocket::{get, put};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct ThresholdsInput {
+    max_weekly_hours: i32,
+    max_weekly_pp: i32,
+}
+
+#[derive(Serialize)]
+pub struct YearThresholds {
+    pub academic_year: String,
+    #[serde(flatten)]
+    pub thresholds: Thresholds,
+}
+
+/// Valida la carga semanal de un balance y devuelve errores y avisos por celda
+#[get("/api/balances/<id>/validate")]
+pub async fn validate(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<ValidationReport>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let non_teaching = load_non_teaching(&db.db, balance.start_date)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let weeks = generate_calendar(balance.start_date, &[], &non_teaching);
+
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let thresholds = thresholds_for(&db.db, &balance.academic_year)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(validate_balance(&allocations, &weeks, &thresholds)))
+}
+
+/// Umbrales vigentes de cada año académico
+#[get("/api/validation/thresholds")]
+pub async fn list_thresholds(
+    _user: AuthenticatedUser,
+    db: &State<AppState>,
+) -> Result<Json<Vec<YearThresholds>>, Status> {
+    let mut list = Vec::with_capacity(ACADEMIC_YEARS.len());
+    for year in ACADEMIC_YEARS {
+        let thresholds = thresholds_for(&db.db, year)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+        list.push(YearThresholds {
+            academic_year: year.to_string(),
+            thresholds,
+        });
+    }
+
+    Ok(Json(list))
+}
+
+/// Configura los umbrales de un año académico - Requiere permiso de configuración
+#[put("/api/validation/thresholds/<academic_year>", format = "json", data = "<input>")]
+pub async fn update_thresholds(
+    _admin: Require<perm::ManageSettings>,
+    academic_year: &str,
+    input: Json<ThresholdsInput>,
+    db: &State<AppState>,
+) -> Result<Json<umbrales_carga::Model>, Status> {
+    if !ACADEMIC_YEARS.contains(&academic_year)
+        || input.max_weekly_hours <= 0
+        || input.max_weekly_pp < 0
+    {
+        return Err(Status::BadRequest);
+    }
+
+    let existing = umbrales_carga::Entity::find_by_id(academic_year.to_string())
+        .one(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let saved = match existing {
+        Some(model) => {
+            let mut active: umbrales_carga::ActiveModel = model.into();
+            active.max_weekly_hours = Set(input.max_weekly_hours);
+            active.max_weekly_pp = Set(input.max_weekly_pp);
+            active.update(&db.db).await
+        }
+        None => {
+            umbrales_carga::ActiveModel {
+                academic_year: Set(academic_year.to_string()),
+                max_weekly_hours: Set(input.max_weekly_hours),
+                max_weekly_pp: Set(input.max_weekly_pp),
+            }
+            .insert(&db.db)
+            .await
+        }
+    }
+    .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(saved))
+}
@@ -0,0 +1,159 @@
+use crate::*;
+use crate::routes::balances::{find_owned_balance, find_visible_balance};
+use crate::utils::jwt::{AuthenticatedUser, Claims, Require};
+use crate::utils::permissions::perm;
+use crate::utils::workflow::{BalanceStatus, Transition};
+use rocket::{get, post};
+use sea_orm::sea_query::Expr;
+use sea_orm::{QueryOrder, TransactionTrait};
+use serde::Deserialize;
+
+/// Comentario opcional de una transición (obligatorio al rechazar)
+#[derive(Deserialize)]
+pub struct TransitionInput {
+    comment: Option<String>,
+}
+
+/// Aplica una transición del flujo de aprobación y la registra en el historial
+async fn apply_transition(
+    db: &DatabaseConnection,
+    balance: balances::Model,
+    transition: Transition,
+    claims: &Claims,
+    input: Option<Json<TransitionInput>>,
+) -> Result<Json<balances::Model>, Status> {
+    let comment = input
+        .and_then(|i| i.into_inner().comment)
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if transition.requires_comment() && comment.is_none() {
+        return Err(Status::BadRequest);
+    }
+
+    let from = BalanceStatus::parse(&balance.status).ok_or(Status::InternalServerError)?;
+    let to = transition.apply(from).ok_or(Status::Conflict)?;
+
+    let txn = db.begin().await.map_err(|_| Status::InternalServerError)?;
+
+    // Solo cambia el estado si sigue siendo el leído: si otra petición lo
+    // cambió entretanto, la transición ya no es válida
+    let result = balances::Entity::update_many()
+        .col_expr(balances::Column::Status, Expr::value(to.as_str()))
+        .filter(balances::Column::Id.eq(balance.id))
+        .filter(balances::Column::Status.eq(from.as_str()))
+        .exec(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    if result.rows_affected == 0 {
+        return Err(Status::Conflict);
+    }
+    let updated = balances::Entity::find_by_id(balance.id)
+        .one(&txn)
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::NotFound)?;
+
+    balance_transiciones::ActiveModel {
+        balance_id: Set(balance.id),
+        from_status: Set(from.as_str().to_string()),
+        to_status: Set(to.as_str().to_string()),
+        user_id: Set(claims.user_id()),
+        comment: Set(comment),
+        created_at: Set(chrono::Local::now().naive_local()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|_| Status::InternalServerError)?;
+
+    txn.commit().await.map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(updated))
+}
+
+/// El coordinador envía el balance a revisión
+#[post("/api/balances/<id>/submit", data = "<input>")]
+pub async fn submit_balance(
+    user: AuthenticatedUser,
+    id: i32,
+    input: Option<Json<TransitionInput>>,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    let balance = find_owned_balance(&db.db, id, &user.0).await?;
+    apply_transition(&db.db, balance, Transition::Submit, &user.0, input).await
+}
+
+/// El Jefe de Colectivo aprueba el balance (queda de solo lectura). Nadie
+/// puede aprobar su propio balance.
+#[post("/api/balances/<id>/approve", data = "<input>")]
+pub async fn approve_balance(
+    approver: Require<perm::ApproveBalances>,
+    id: i32,
+    input: Option<Json<TransitionInput>>,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    let balance = find_visible_balance(&db.db, id, &approver.0).await?;
+    if balance.user_id == approver.0.user_id() {
+        return Err(Status::Forbidden);
+    }
+    apply_transition(&db.db, balance, Transition::Approve, &approver.0, input).await
+}
+
+/// El Jefe de Colectivo rechaza el balance indicando el motivo
+#[post("/api/balances/<id>/reject", data = "<input>")]
+pub async fn reject_balance(
+    approver: Require<perm::ApproveBalances>,
+    id: i32,
+    input: Option<Json<TransitionInput>>,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    let balance = find_visible_balance(&db.db, id, &approver.0).await?;
+    apply_transition(&db.db, balance, Transition::Reject, &approver.0, input).await
+}
+
+/// El Jefe de Colectivo reabre un balance para que vuelva a editarse
+#[post("/api/balances/<id>/reopen", data = "<input>")]
+pub async fn reopen_balance(
+    approver: Require<perm::ApproveBalances>,
+    id: i32,
+    input: Option<Json<TransitionInput>>,
+    db: &State<AppState>,
+) -> Result<Json<balances::Model>, Status> {
+    let balance = find_visible_balance(&db.db, id, &approver.0).await?;
+    apply_transition(&db.db, balance, Transition::Reopen, &approver.0, input).await
+}
+
+/// Historial de estados de un balance
+#[get("/api/balances/<id>/transitions")]
+pub async fn list_transitions(
+    user: AuthenticatedUser,
+    id: i32,
+    db: &State<AppState>,
+) -> Result<Json<Vec<balance_transiciones::Model>>, Status> {
+    let balance = find_visible_balance(&db.db, id, &user.0).await?;
+
+    let list = balance_transiciones::Entity::find()
+        .filter(balance_transiciones::Column::BalanceId.eq(balance.id))
+        .order_by_asc(balance_transiciones::Column::CreatedAt)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
+
+/// Balances pendientes de aprobación
+#[get("/api/balances/pending")]
+pub async fn pending_balances(
+    _approver: Require<perm::ApproveBalances>,
+    db: &State<AppState>,
+) -> Result<Json<Vec<balances::Model>>, Status> {
+    let list = balances::Entity::find()
+        .filter(balances::Column::Status.eq(BalanceStatus::Enviado.as_str()))
+        .order_by_asc(balances::Column::UpdatedAt)
+        .all(&db.db)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    Ok(Json(list))
+}
@@ -0,0 +1,267 @@
+use crate::dias_no_lectivos;
+use crate::utils::totals::{
+    CONSULTATION_WEEK, EXTRA_II_WEEK, EXTRA_I_WEEK, FINAL_EXAMS_WEEK, TOTAL_WEEKS,
+};
+use chrono::{Datelike, Duration, NaiveDate};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+
+/// Días de clase de una semana lectiva o de consultas (columnas 1-4 de la tabla)
+pub const TEACHING_DAYS: i64 = 4;
+/// Días de una semana de exámenes (columnas 1-5 de la tabla)
+pub const EXAM_DAYS: i64 = 5;
+
+/// Tipo de columna de semana en el balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekKind {
+    Teaching,
+    Consultation,
+    FinalExams,
+    ExtraI,
+    ExtraII,
+}
+
+impl WeekKind {
+    /// Tipo de semana según su número en el balance
+    pub fn of(week: i32) -> Self {
+        match week {
+            CONSULTATION_WEEK => WeekKind::Consultation,
+            FINAL_EXAMS_WEEK => WeekKind::FinalExams,
+            EXTRA_I_WEEK => WeekKind::ExtraI,
+            EXTRA_II_WEEK => WeekKind::ExtraII,
+            _ => WeekKind::Teaching,
+        }
+    }
+
+    /// Indica si es una semana de exámenes (finales o extraordinarios)
+    pub fn is_exam(&self) -> bool {
+        matches!(self, WeekKind::FinalExams | WeekKind::ExtraI | WeekKind::ExtraII)
+    }
+
+    /// Número de días (columnas) de la semana
+    pub fn days(&self) -> i64 {
+        if self.is_exam() { EXAM_DAYS } else { TEACHING_DAYS }
+    }
+}
+
+/// Etiqueta de la semana tal y como aparece en la cabecera de la tabla
+pub fn week_label(week: i32) -> String {
+    match WeekKind::of(week) {
+        WeekKind::Teaching => format!("Semana {}", week),
+        WeekKind::Consultation => "SEMANA DE CONSULTAS".to_string(),
+        WeekKind::FinalExams => "EXÁMENES FINALES".to_string(),
+        WeekKind::ExtraI => "EXÁMENES EXTRA I".to_string(),
+        WeekKind::ExtraII => "EXÁMENES EXTRA II".to_string(),
+    }
+}
+
+/// Día perdido por un feriado, vacaciones o evento institucional
+#[derive(Debug, Clone, Serialize)]
+pub struct LostDay {
+    pub date: NaiveDate,
+    pub description: String,
+}
+
+/// Fechas reales de una columna de semana
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarWeek {
+    pub week: i32,
+    pub label: String,
+    pub kind: WeekKind,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<NaiveDate>,
+    /// Días de la semana que caen en un período no lectivo
+    pub lost_days: Vec<LostDay>,
+    /// Días disponibles (`days` menos `lost_days`)
+    pub available_days: usize,
+    /// La semana perdió días y su capacidad está reducida
+    pub reduced: bool,
+}
+
+impl CalendarWeek {
+    /// Fracción de la capacidad normal de la semana (1.0 = semana completa)
+    pub fn capacity_factor(&self) -> f64 {
+        if self.days.is_empty() {
+            return 0.0;
+        }
+        self.available_days as f64 / self.days.len() as f64
+    }
+}
+
+/// Lunes de la semana que contiene la fecha
+pub fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Indica si la fecha de inicio corresponde al período: el 1er semestre
+/// ("1ero") empieza entre agosto y diciembre y el 2do ("2do"), entre enero y
+/// junio. Cualquier otro período no es válido.
+pub fn starts_in_period(period: &str, start_date: NaiveDate) -> bool {
+    match period {
+        "1ero" => (8..=12).contains(&start_date.month()),
+        "2do" => (1..=6).contains(&start_date.month()),
+        _ => false,
+    }
+}
+
+/// Período no lectivo que contiene la fecha, si existe
+fn non_teaching_on(
+    date: NaiveDate,
+    non_teaching: &[dias_no_lectivos::Model],
+) -> Option<&dias_no_lectivos::Model> {
+    non_teaching
+        .iter()
+        .find(|p| p.start_date <= date && date <= p.end_date)
+}
+
+/// Genera las fechas de las semanas 1-15, consultas, finales, extra I y extra II
+/// a partir de la fecha de inicio. Se saltan, sin consumir número de semana,
+/// las semanas cuyo lunes esté en `skipped` y las que pierden todos sus días
+/// por períodos no lectivos. Las que pierden solo algunos días se marcan como
+/// `reduced`.
+pub fn generate_calendar(
+    start_date: NaiveDate,
+    skipped: &[NaiveDate],
+    non_teaching: &[dias_no_lectivos::Model],
+) -> Vec<CalendarWeek> {
+    let skipped: Vec<NaiveDate> = skipped.iter().map(|d| monday_of(*d)).collect();
+    let mut monday = monday_of(start_date);
+    let mut weeks = Vec::with_capacity(TOTAL_WEEKS as usize);
+
+    for week in 1..=TOTAL_WEEKS {
+        let kind = WeekKind::of(week);
+
+        let (days, lost_days) = loop {
+            let days: Vec<NaiveDate> =
+                (0..kind.days()).map(|d| monday + Duration::days(d)).collect();
+            let lost_days: Vec<LostDay> = days
+                .iter()
+                .filter_map(|d| {
+                    non_teaching_on(*d, non_teaching).map(|p| LostDay {
+                        date: *d,
+                        description: p.description.clone(),
+                    })
+                })
+                .collect();
+
+            if skipped.contains(&monday) || lost_days.len() == days.len() {
+                monday += Duration::weeks(1);
+                continue;
+            }
+            break (days, lost_days);
+        };
+
+        weeks.push(CalendarWeek {
+            week,
+            label: week_label(week),
+            kind,
+            start: monday,
+            end: *days.last().unwrap_or(&monday),
+            available_days: days.len() - lost_days.len(),
+            reduced: !lost_days.is_empty(),
+            days,
+            lost_days,
+        });
+
+        monday += Duration::weeks(1);
+    }
+
+    weeks
+}
+
+/// Carga los períodos no lectivos que pueden afectar a un calendario que
+/// empieza en `start_date` (un año a partir de esa fecha)
+pub async fn load_non_teaching(
+    db: &DatabaseConnection,
+    start_date: NaiveDate,
+) -> Result<Vec<dias_no_lectivos::Model>, DbErr> {
+    let from = monday_of(start_date);
+    let to = from + Duration::weeks(52);
+
+    dias_no_lectivos::Entity::find()
+        .filter(dias_no_lectivos::Column::EndDate.gte(from))
+        .filter(dias_no_lectivos::Column::StartDate.lte(to))
+        .order_by_asc(dias_no_lectivos::Column::StartDate)
+        .all(db)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn period(description: &str, start: NaiveDate, end: NaiveDate) -> dias_no_lectivos::Model {
+        dias_no_lectivos::Model {
+            id: 0,
+            description: description.to_string(),
+            kind: "feriado".to_string(),
+            start_date: start,
+            end_date: end,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn weeks_start_on_the_monday_of_the_start_date() {
+        // Miércoles 10/09/2025
+        let weeks = generate_calendar(date(2025, 9, 10), &[], &[]);
+
+        assert_eq!(weeks.len(), TOTAL_WEEKS as usize);
+        assert_eq!(weeks[0].start, date(2025, 9, 8));
+        assert_eq!(weeks[0].days.len(), TEACHING_DAYS as usize);
+        assert_eq!(weeks[0].end, date(2025, 9, 11));
+        assert_eq!(weeks[15].kind, WeekKind::Consultation);
+        assert_eq!(weeks[16].kind, WeekKind::FinalExams);
+        assert_eq!(weeks[16].days.len(), EXAM_DAYS as usize);
+        assert_eq!(weeks[18].label, "EXÁMENES EXTRA II");
+        assert!(weeks.iter().all(|w| !w.reduced && w.capacity_factor() == 1.0));
+    }
+
+    #[test]
+    fn skipped_weeks_do_not_consume_a_week_number() {
+        let weeks = generate_calendar(date(2025, 9, 8), &[date(2025, 9, 17)], &[]);
+
+        assert_eq!(weeks[0].start, date(2025, 9, 8));
+        assert_eq!(weeks[1].start, date(2025, 9, 22));
+    }
+
+    #[test]
+    fn a_holiday_reduces_its_week() {
+        let holiday = period("Feriado", date(2025, 10, 8), date(2025, 10, 8));
+        let weeks = generate_calendar(date(2025, 9, 8), &[], &[holiday]);
+
+        let week = &weeks[4];
+        assert_eq!(week.start, date(2025, 10, 6));
+        assert!(week.reduced);
+        assert_eq!(week.available_days, 3);
+        assert_eq!(week.capacity_factor(), 0.75);
+        assert_eq!(week.lost_days[0].date, date(2025, 10, 8));
+        assert_eq!(week.lost_days[0].description, "Feriado");
+        assert_eq!(weeks.iter().filter(|w| w.reduced).count(), 1);
+    }
+
+    #[test]
+    fn the_start_date_must_fall_in_the_period() {
+        assert!(starts_in_period("1ero", date(2025, 9, 8)));
+        assert!(starts_in_period("2do", date(2026, 2, 2)));
+        assert!(!starts_in_period("1ero", date(2026, 2, 2)));
+        assert!(!starts_in_period("2do", date(2025, 9, 8)));
+        assert!(!starts_in_period("3ro", date(2025, 9, 8)));
+    }
+
+    #[test]
+    fn a_week_without_available_days_is_skipped() {
+        let vacation = period("Receso", date(2025, 10, 13), date(2025, 10, 17));
+        let weeks = generate_calendar(date(2025, 9, 8), &[], &[vacation]);
+
+        assert_eq!(weeks[4].start, date(2025, 10, 6));
+        assert_eq!(weeks[5].start, date(2025, 10, 20));
+        assert!(!weeks[5].reduced);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::balances;
+use crate::utils::totals::{ActivityType, BalanceTotals, TypeHours};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Resumen de un balance comparado
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedBalance {
+    pub id: i32,
+    pub year_label: String,
+    pub period: String,
+    pub school_year: String,
+    pub hours: TypeHours,
+    pub total: i32,
+    pub coef: f64,
+    /// Curva de carga: total de horas de cada semana (1-19)
+    pub weekly_load: Vec<i32>,
+}
+
+/// Horas de una asignatura en uno de los balances comparados
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectHours {
+    pub balance_id: i32,
+    pub asignatura_id: i32,
+    pub hours: TypeHours,
+    pub total: i32,
+}
+
+/// Asignatura (identificada por su nombre) cuyas horas no coinciden en todos
+/// los balances, o que falta en alguno
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectChange {
+    pub name: String,
+    pub present_in: Vec<SubjectHours>,
+    pub missing_in: Vec<i32>,
+}
+
+/// Diferencia de un balance respecto al primero de la comparación
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceDelta {
+    pub balance_id: i32,
+    pub hours: TypeHours,
+    pub total: i32,
+    pub weekly_load: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonReport {
+    pub balances: Vec<ComparedBalance>,
+    /// Diferencias de cada balance respecto al primero (balance - primero)
+    pub deltas: Vec<BalanceDelta>,
+    pub changed_subjects: Vec<SubjectChange>,
+}
+
+/// Compara varios balances (en el orden recibido): curvas de carga semanal,
+/// totales por tipo y asignaturas que cambian. Las asignaturas se emparejan
+/// por nombre, ya que de un curso a otro pueden tener otro ID.
+pub fn compare_balances(entries: &[(balances::Model, String, BalanceTotals)]) -> ComparisonReport {
+    let balances: Vec<ComparedBalance> = entries
+        .iter()
+        .map(|(balance, year_label, totals)| ComparedBalance {
+            id: balance.id,
+            year_label: year_label.clone(),
+            period: balance.period.clone(),
+            school_year: balance.school_year.clone(),
+            hours: totals.hours,
+            total: totals.total,
+            coef: totals.coef,
+            weekly_load: totals.weeks.iter().map(|w| w.total).collect(),
+        })
+        .collect();
+
+    let deltas = match balances.first() {
+        Some(first) => balances
+            .iter()
+            .skip(1)
+            .map(|b| {
+                let mut hours = b.hours;
+                for activity in ActivityType::ALL {
+                    hours.set(activity, b.hours.get(activity) - first.hours.get(activity));
+                }
+                BalanceDelta {
+                    balance_id: b.id,
+                    hours,
+                    total: b.total - first.total,
+                    weekly_load: b
+                        .weekly_load
+                        .iter()
+                        .zip(&first.weekly_load)
+                        .map(|(b, f)| b - f)
+                        .collect(),
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // Horas de cada asignatura (por nombre) en cada balance
+    let mut by_name: BTreeMap<String, (String, Vec<SubjectHours>)> = BTreeMap::new();
+    for (balance, _, totals) in entries {
+        for subject in totals.subjects.iter().filter(|s| s.total > 0) {
+            by_name
+                .entry(subject.name.trim().to_lowercase())
+                .or_insert_with(|| (subject.name.clone(), Vec::new()))
+                .1
+                .push(SubjectHours {
+                    balance_id: balance.id,
+                    asignatura_id: subject.asignatura_id,
+                    hours: subject.hours,
+                    total: subject.total,
+                });
+        }
+    }
+
+    let changed_subjects = by_name
+        .into_values()
+        .filter_map(|(name, present_in)| {
+            let missing_in: Vec<i32> = entries
+                .iter()
+                .map(|(b, _, _)| b.id)
+                .filter(|id| !present_in.iter().any(|p| p.balance_id == *id))
+                .collect();
+            let same_hours = present_in.windows(2).all(|w| w[0].hours == w[1].hours);
+            if missing_in.is_empty() && same_hours {
+                return None;
+            }
+            Some(SubjectChange {
+                name,
+                present_in,
+                missing_in,
+            })
+        })
+        .collect();
+
+    ComparisonReport {
+        balances,
+        deltas,
+        changed_subjects,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::totals::tests::{allocation, subject};
+    use crate::utils::totals::{compute_totals, GroupLayout};
+    use crate::{asignaciones_horas, asignaturas};
+    use chrono::NaiveDate;
+
+    fn entry(
+        id: i32,
+        subjects: &[asignaturas::Model],
+        allocations: &[asignaciones_horas::Model],
+    ) -> (balances::Model, String, BalanceTotals) {
+        let balance = balances::Model {
+            id,
+            user_id: 1,
+            academic_year: "2do".to_string(),
+            period: "1ero".to_string(),
+            school_year: format!("{}-{}", 2023 + id, 2024 + id),
+            start_date: NaiveDate::from_ymd_opt(2025, 9, 8).unwrap(),
+            status: "borrador".to_string(),
+            carrera_id: None,
+            created_at: None,
+            updated_at: None,
+        };
+        let totals = compute_totals(subjects, allocations, GroupLayout::default());
+        (balance, "2do".to_string(), totals)
+    }
+
+    #[test]
+    fn deltas_are_relative_to_the_first_balance() {
+        let subjects = [subject(1, "Matemática")];
+        let first = entry(1, &subjects, &[allocation(1, 1, TypeHours { c: 2, ..Default::default() })]);
+        let second = entry(
+            2,
+            &subjects,
+            &[
+                allocation(1, 1, TypeHours { c: 2, ..Default::default() }),
+                allocation(1, 2, TypeHours { cp: 2, ..Default::default() }),
+            ],
+        );
+
+        let report = compare_balances(&[first, second]);
+
+        assert_eq!(report.balances.len(), 2);
+        assert_eq!(report.balances[1].weekly_load[..2], [2, 2]);
+        assert_eq!(report.deltas.len(), 1);
+        let delta = &report.deltas[0];
+        assert_eq!(delta.balance_id, 2);
+        assert_eq!((delta.hours.c, delta.hours.cp), (0, 2));
+        assert_eq!(delta.total, 2);
+        assert_eq!(delta.weekly_load[..2], [0, 2]);
+    }
+
+    #[test]
+    fn subjects_are_matched_by_name() {
+        let hours = TypeHours { c: 2, ..Default::default() };
+        let first = entry(1, &[subject(1, "Matemática")], &[allocation(1, 1, hours)]);
+        let second = entry(2, &[subject(5, " matemática ")], &[allocation(5, 1, hours)]);
+
+        let report = compare_balances(&[first, second]);
+
+        assert!(report.changed_subjects.is_empty());
+    }
+
+    #[test]
+    fn changed_and_missing_subjects_are_reported() {
+        let first = entry(
+            1,
+            &[subject(1, "Matemática"), subject(2, "Física")],
+            &[
+                allocation(1, 1, TypeHours { c: 2, ..Default::default() }),
+                allocation(2, 1, TypeHours { c: 2, ..Default::default() }),
+            ],
+        );
+        let second = entry(
+            2,
+            &[subject(3, "Matemática")],
+            &[allocation(3, 1, TypeHours { c: 4, ..Default::default() })],
+        );
+
+        let report = compare_balances(&[first, second]);
+
+        let changes: Vec<(&str, usize, &[i32])> = report
+            .changed_subjects
+            .iter()
+            .map(|s| (s.name.as_str(), s.present_in.len(), s.missing_in.as_slice()))
+            .collect();
+        assert_eq!(changes, [("Física", 1, &[2][..]), ("Matemática", 2, &[][..])]);
+        assert_eq!(report.changed_subjects[1].present_in[1].asignatura_id, 3);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{asignaciones_horas, asignaturas, horas_plan};
+use crate::utils::totals::{ActivityType, TypeHours};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Tipo de desviación respecto al plan de estudio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviationKind {
+    /// El balance planifica menos horas que el plan
+    Under,
+    /// El balance planifica más horas que el plan
+    Over,
+}
+
+/// Desviación de un tipo de actividad de una asignatura
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeDeviation {
+    pub activity: ActivityType,
+    pub target: i32,
+    pub scheduled: i32,
+    pub difference: i32,
+    pub kind: DeviationKind,
+}
+
+/// Comparación de una asignatura: horas del plan, horas del balance y la
+/// diferencia (balance - plan) por tipo de actividad
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectDeviation {
+    pub asignatura_id: i32,
+    pub name: String,
+    pub target: Option<TypeHours>,
+    pub scheduled: TypeHours,
+    pub difference: TypeHours,
+    pub deviations: Vec<TypeDeviation>,
+}
+
+/// Informe plan vs. balance
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviationReport {
+    pub balance_id: i32,
+    pub plan_id: i32,
+    pub matches_plan: bool,
+    pub under: usize,
+    pub over: usize,
+    pub without_target: usize,
+    pub subjects: Vec<SubjectDeviation>,
+}
+
+/// Compara las horas planificadas en el balance con las horas oficiales del
+/// plan, por asignatura y tipo de actividad. Las filas siguen el orden de
+/// `subjects`; las asignaturas sin horas en el plan se informan sin desviaciones.
+pub fn compare_with_plan(
+    balance_id: i32,
+    plan_id: i32,
+    subjects: &[asignaturas::Model],
+    allocations: &[asignaciones_horas::Model],
+    targets: &[horas_plan::Model],
+) -> DeviationReport {
+    let mut scheduled: HashMap<i32, TypeHours> = HashMap::new();
+    for allocation in allocations {
+        *scheduled.entry(allocation.asignatura_id).or_default() += TypeHours::from(allocation);
+    }
+    let targets: HashMap<i32, TypeHours> = targets
+        .iter()
+        .map(|t| (t.asignatura_id, TypeHours::from(t)))
+        .collect();
+
+    let subjects: Vec<SubjectDeviation> = subjects
+        .iter()
+        .map(|subject| {
+            let hours = scheduled.get(&subject.id).copied().unwrap_or_default();
+            let target = targets.get(&subject.id).copied();
+
+            let mut difference = TypeHours::default();
+            let mut deviations = Vec::new();
+            if let Some(target) = target {
+                for activity in ActivityType::ALL {
+                    let diff = hours.get(activity) - target.get(activity);
+                    difference.set(activity, diff);
+                    if diff != 0 {
+                        deviations.push(TypeDeviation {
+                            activity,
+                            target: target.get(activity),
+                            scheduled: hours.get(activity),
+                            difference: diff,
+                            kind: if diff < 0 {
+                                DeviationKind::Under
+                            } else {
+                                DeviationKind::Over
+                            },
+                        });
+                    }
+                }
+            }
+
+            SubjectDeviation {
+                asignatura_id: subject.id,
+                name: subject.name.clone(),
+                target,
+                scheduled: hours,
+                difference,
+                deviations,
+            }
+        })
+        .collect();
+
+    let count = |kind: DeviationKind| {
+        subjects
+            .iter()
+            .flat_map(|s| &s.deviations)
+            .filter(|d| d.kind == kind)
+            .count()
+    };
+    let under = count(DeviationKind::Under);
+    let over = count(DeviationKind::Over);
+    let without_target = subjects.iter().filter(|s| s.target.is_none()).count();
+
+    DeviationReport {
+        balance_id,
+        plan_id,
+        matches_plan: under == 0 && over == 0 && without_target == 0,
+        under,
+        over,
+        without_target,
+        subjects,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::totals::tests::{allocation, subject};
+
+    fn target(asignatura_id: i32, hours: TypeHours) -> horas_plan::Model {
+        horas_plan::Model {
+            id: 0,
+            plan_id: 1,
+            asignatura_id,
+            c: hours.c,
+            cp: hours.cp,
+            s: hours.s,
+            pl: hours.pl,
+            te: hours.te,
+            t: hours.t,
+            pp: hours.pp,
+        }
+    }
+
+    #[test]
+    fn a_balance_with_the_plan_hours_matches_it() {
+        let subjects = [subject(1, "Matemática")];
+        let allocations = [
+            allocation(1, 1, TypeHours { c: 2, cp: 2, ..Default::default() }),
+            allocation(1, 2, TypeHours { c: 2, ..Default::default() }),
+        ];
+        let targets = [target(1, TypeHours { c: 4, cp: 2, ..Default::default() })];
+
+        let report = compare_with_plan(7, 1, &subjects, &allocations, &targets);
+
+        assert!(report.matches_plan);
+        assert_eq!((report.balance_id, report.plan_id), (7, 1));
+        assert!(report.subjects[0].deviations.is_empty());
+        assert_eq!(report.subjects[0].difference, TypeHours::default());
+    }
+
+    #[test]
+    fn hours_below_and_above_the_plan_are_deviations() {
+        let subjects = [subject(1, "Matemática")];
+        let allocations = [
+            allocation(1, 1, TypeHours { c: 2, cp: 2, ..Default::default() }),
+            allocation(1, 2, TypeHours { cp: 2, ..Default::default() }),
+        ];
+        let targets = [target(1, TypeHours { c: 4, cp: 2, ..Default::default() })];
+
+        let report = compare_with_plan(7, 1, &subjects, &allocations, &targets);
+
+        assert!(!report.matches_plan);
+        assert_eq!((report.under, report.over, report.without_target), (1, 1, 0));
+        let deviations: Vec<(ActivityType, i32, DeviationKind)> = report.subjects[0]
+            .deviations
+            .iter()
+            .map(|d| (d.activity, d.difference, d.kind))
+            .collect();
+        assert_eq!(
+            deviations,
+            [
+                (ActivityType::C, -2, DeviationKind::Under),
+                (ActivityType::CP, 2, DeviationKind::Over),
+            ]
+        );
+    }
+
+    #[test]
+    fn subjects_without_target_are_reported_apart() {
+        let subjects = [subject(1, "Matemática"), subject(2, "Física")];
+        let allocations = [allocation(2, 1, TypeHours { c: 2, ..Default::default() })];
+        let targets = [target(1, TypeHours::default())];
+
+        let report = compare_with_plan(7, 1, &subjects, &allocations, &targets);
+
+        assert!(!report.matches_plan);
+        assert_eq!((report.under, report.over, report.without_target), (0, 0, 1));
+        assert_eq!(report.subjects[1].target, None);
+        assert_eq!(report.subjects[1].scheduled.c, 2);
+        assert!(report.subjects[1].deviations.is_empty());
+    }
+}
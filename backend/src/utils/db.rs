@@ -1,4 +1,5 @@
-use sea_orm::{DatabaseConnection, Database};
+use rocket::http::Status;
+use sea_orm::{DatabaseConnection, Database, DbErr, SqlErr};
 
 pub async fn establish_connection() -> DatabaseConnection {
     dotenvy::dotenv().ok();
@@ -11,4 +12,23 @@ pub async fn establish_connection() -> DatabaseConnection {
 
     println!("✅ Conectado a la base de datos exitosamente");
     db
-}
\ No newline at end of file
+}
+
+/// Traduce un error de escritura: los duplicados son un conflicto y las
+/// referencias a registros inexistentes, una petición incorrecta
+pub fn write_error(err: DbErr) -> Status {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => Status::Conflict,
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => Status::BadRequest,
+        _ => Status::InternalServerError,
+    }
+}
+
+/// Traduce un error de borrado: un registro todavía referenciado por otros
+/// es un conflicto
+pub fn delete_error(err: DbErr) -> Status {
+    match err.sql_err() {
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => Status::Conflict,
+        _ => Status::InternalServerError,
+    }
+}
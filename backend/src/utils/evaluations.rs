@@ -0,0 +1,330 @@
+use crate::{asignaciones_horas, fechas_evaluacion};
+use crate::utils::calendar::{CalendarWeek, WeekKind};
+use crate::utils::validation::{tests_per_week, Thresholds};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Máximo de semanas alternativas propuestas por prueba parcial
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Pruebas parciales (PP) planificadas en una semana lectiva
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationWeek {
+    pub week: i32,
+    pub label: String,
+    pub asignaturas: Vec<i32>,
+    pub count: i32,
+    /// Pruebas que aún caben en la semana sin superar el máximo
+    pub spare: i32,
+    pub overloaded: bool,
+}
+
+/// Semanas alternativas para una prueba de una semana sobrecargada
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub asignatura_id: i32,
+    pub week: i32,
+    pub alternatives: Vec<i32>,
+}
+
+/// Varias pruebas del mismo año el mismo día
+#[derive(Debug, Clone, Serialize)]
+pub struct Collision {
+    pub date: NaiveDate,
+    pub week: Option<i32>,
+    pub asignaturas: Vec<i32>,
+}
+
+/// Fecha de prueba que no coincide con una semana con PP de la asignatura
+#[derive(Debug, Clone, Serialize)]
+pub struct MisplacedDate {
+    pub asignatura_id: i32,
+    pub date: NaiveDate,
+    pub week: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationReport {
+    pub valid: bool,
+    pub max_weekly_pp: i32,
+    pub weeks: Vec<EvaluationWeek>,
+    pub overloaded_weeks: Vec<i32>,
+    pub suggestions: Vec<Suggestion>,
+    pub collisions: Vec<Collision>,
+    pub misplaced_dates: Vec<MisplacedDate>,
+}
+
+/// Semana del calendario que contiene una fecha
+pub fn week_of(weeks: &[CalendarWeek], date: NaiveDate) -> Option<i32> {
+    weeks.iter().find(|w| w.days.contains(&date)).map(|w| w.week)
+}
+
+/// Indica si se pueden guardar las fechas de pruebas parciales
+/// (asignatura, fecha): cada una es de una asignatura con horas en el
+/// balance, cae en un día disponible de una semana lectiva y no se repite
+pub fn dates_are_valid(
+    dates: &[(i32, NaiveDate)],
+    allocations: &[asignaciones_horas::Model],
+    weeks: &[CalendarWeek],
+) -> bool {
+    let subjects: BTreeSet<i32> = allocations.iter().map(|a| a.asignatura_id).collect();
+    let available = |date: NaiveDate| {
+        weeks.iter().find(|w| w.days.contains(&date)).is_some_and(|w| {
+            w.kind == WeekKind::Teaching && !w.lost_days.iter().any(|d| d.date == date)
+        })
+    };
+    let unique: BTreeSet<&(i32, NaiveDate)> = dates.iter().collect();
+
+    unique.len() == dates.len()
+        && dates
+            .iter()
+            .all(|(asignatura_id, date)| subjects.contains(asignatura_id) && available(*date))
+}
+
+/// Revisa la distribución de las pruebas parciales de un balance:
+///
+/// - semanas con más PP que `max_weekly_pp`, con semanas alternativas para
+///   cada prueba (semanas lectivas con hueco, sin PP de la misma asignatura y
+///   con días disponibles, las más cercanas primero);
+/// - varias pruebas el mismo día, cuando ya se conocen las fechas exactas;
+/// - fechas que caen fuera de las semanas donde la asignatura tiene PP.
+pub fn check_evaluations(
+    allocations: &[asignaciones_horas::Model],
+    dates: &[fechas_evaluacion::Model],
+    weeks: &[CalendarWeek],
+    thresholds: &Thresholds,
+) -> EvaluationReport {
+    let max = thresholds.max_weekly_pp;
+
+    let tests = tests_per_week(allocations);
+
+    let teaching: Vec<&CalendarWeek> = weeks
+        .iter()
+        .filter(|w| w.kind == WeekKind::Teaching)
+        .collect();
+
+    let summary: Vec<EvaluationWeek> = teaching
+        .iter()
+        .map(|w| {
+            let asignaturas: Vec<i32> = tests
+                .get(&w.week)
+                .map(|s| s.iter().copied().collect())
+                .unwrap_or_default();
+            let count = asignaturas.len() as i32;
+            EvaluationWeek {
+                week: w.week,
+                label: w.label.clone(),
+                asignaturas,
+                count,
+                spare: (max - count).max(0),
+                overloaded: count > max,
+            }
+        })
+        .collect();
+
+    let overloaded_weeks: Vec<i32> = summary
+        .iter()
+        .filter(|w| w.overloaded)
+        .map(|w| w.week)
+        .collect();
+
+    // Las pruebas que sobran en una semana se reparten entre las semanas con
+    // hueco. Cada semana propuesta queda reservada para esa prueba, de modo
+    // que cualquiera de las alternativas que se elija sigue cabiendo.
+    let mut spare: BTreeMap<i32, i32> = summary
+        .iter()
+        .filter(|w| teaching.iter().any(|t| t.week == w.week && t.available_days > 0))
+        .map(|w| (w.week, w.spare))
+        .collect();
+    let mut occupied = tests.clone();
+
+    let mut suggestions = Vec::new();
+    for week in summary.iter().filter(|w| w.overloaded) {
+        for &asignatura_id in week.asignaturas.iter().skip(max.max(0) as usize) {
+            let mut candidates: Vec<i32> = spare
+                .iter()
+                .filter(|(w, free)| {
+                    **free > 0
+                        && !occupied.get(w).is_some_and(|s| s.contains(&asignatura_id))
+                })
+                .map(|(w, _)| *w)
+                .collect();
+            candidates.sort_by_key(|w| ((w - week.week).abs(), *w));
+            candidates.truncate(MAX_SUGGESTIONS);
+
+            for candidate in &candidates {
+                if let Some(free) = spare.get_mut(candidate) {
+                    *free -= 1;
+                }
+                occupied.entry(*candidate).or_default().insert(asignatura_id);
+            }
+
+            suggestions.push(Suggestion {
+                asignatura_id,
+                week: week.week,
+                alternatives: candidates,
+            });
+        }
+    }
+
+    let mut per_day: BTreeMap<NaiveDate, BTreeSet<i32>> = BTreeMap::new();
+    for date in dates {
+        per_day.entry(date.date).or_default().insert(date.asignatura_id);
+    }
+    let collisions: Vec<Collision> = per_day
+        .into_iter()
+        .filter(|(_, subjects)| subjects.len() > 1)
+        .map(|(date, subjects)| Collision {
+            date,
+            week: week_of(weeks, date),
+            asignaturas: subjects.into_iter().collect(),
+        })
+        .collect();
+
+    let misplaced_dates: Vec<MisplacedDate> = dates
+        .iter()
+        .filter_map(|d| {
+            let week = week_of(weeks, d.date);
+            let scheduled = week
+                .and_then(|w| tests.get(&w))
+                .is_some_and(|s| s.contains(&d.asignatura_id));
+            (!scheduled).then_some(MisplacedDate {
+                asignatura_id: d.asignatura_id,
+                date: d.date,
+                week,
+            })
+        })
+        .collect();
+
+    EvaluationReport {
+        valid: overloaded_weeks.is_empty() && collisions.is_empty(),
+        max_weekly_pp: max,
+        weeks: summary,
+        overloaded_weeks,
+        suggestions,
+        collisions,
+        misplaced_dates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::calendar::generate_calendar;
+    use crate::utils::totals::tests::allocation;
+    use crate::utils::totals::TypeHours;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn weeks() -> Vec<CalendarWeek> {
+        generate_calendar(date(2025, 9, 8), &[], &[])
+    }
+
+    fn test(asignatura_id: i32, week: i32) -> asignaciones_horas::Model {
+        allocation(asignatura_id, week, TypeHours { pp: 1, ..Default::default() })
+    }
+
+    fn exam_date(asignatura_id: i32, date: NaiveDate) -> fechas_evaluacion::Model {
+        fechas_evaluacion::Model {
+            id: 0,
+            balance_id: 1,
+            asignatura_id,
+            date,
+        }
+    }
+
+    fn max_pp(max_weekly_pp: i32) -> Thresholds {
+        Thresholds {
+            max_weekly_pp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn spread_out_tests_are_valid() {
+        let report = check_evaluations(&[test(1, 3), test(2, 5)], &[], &weeks(), &max_pp(1));
+
+        assert!(report.valid);
+        assert_eq!(report.weeks.len(), 15);
+        assert_eq!(report.weeks[2].asignaturas, [1]);
+        assert_eq!(report.weeks[2].spare, 0);
+        assert!(report.suggestions.is_empty());
+    }
+
+    #[test]
+    fn each_suggested_week_is_reserved() {
+        let allocations = [test(1, 3), test(2, 3), test(3, 3)];
+
+        let report = check_evaluations(&allocations, &[], &weeks(), &max_pp(1));
+
+        assert!(!report.valid);
+        assert_eq!(report.overloaded_weeks, [3]);
+        let suggested: Vec<(i32, Vec<i32>)> = report
+            .suggestions
+            .iter()
+            .map(|s| (s.asignatura_id, s.alternatives.clone()))
+            .collect();
+        // Las semanas propuestas a la asignatura 2 ya no se ofrecen a la 3
+        assert_eq!(suggested, [(2, vec![2, 4, 1]), (3, vec![5, 6, 7])]);
+    }
+
+    #[test]
+    fn weeks_with_a_test_of_the_same_subject_are_not_suggested() {
+        let allocations = [test(1, 3), test(2, 3), test(3, 3), test(3, 2)];
+
+        let report = check_evaluations(&allocations, &[], &weeks(), &max_pp(2));
+
+        assert_eq!(report.suggestions.len(), 1);
+        assert_eq!(report.suggestions[0].asignatura_id, 3);
+        assert_eq!(report.suggestions[0].alternatives, [4, 1, 5]);
+    }
+
+    #[test]
+    fn tests_on_the_same_day_collide() {
+        let day = date(2025, 9, 23);
+        let dates = [exam_date(1, day), exam_date(2, day)];
+
+        let report = check_evaluations(&[test(1, 3), test(2, 3)], &dates, &weeks(), &max_pp(2));
+
+        assert!(!report.valid);
+        assert_eq!(report.collisions.len(), 1);
+        assert_eq!(report.collisions[0].week, Some(3));
+        assert_eq!(report.collisions[0].asignaturas, [1, 2]);
+        assert!(report.misplaced_dates.is_empty());
+    }
+
+    #[test]
+    fn dates_must_belong_to_subjects_of_the_balance() {
+        let allocations = [test(1, 3), allocation(2, 1, TypeHours { c: 2, ..Default::default() })];
+
+        let dates = [(1, date(2025, 9, 23)), (2, date(2025, 9, 9))];
+
+        assert!(dates_are_valid(&dates, &allocations, &weeks()));
+        assert!(!dates_are_valid(&[(3, date(2025, 9, 23))], &allocations, &weeks()));
+    }
+
+    #[test]
+    fn dates_must_be_unique_teaching_days() {
+        let allocations = [test(1, 3)];
+        let day = date(2025, 9, 23);
+
+        assert!(!dates_are_valid(&[(1, day), (1, day)], &allocations, &weeks()));
+        // Semana de consultas y fecha anterior al inicio del balance
+        assert!(!dates_are_valid(&[(1, date(2025, 12, 23))], &allocations, &weeks()));
+        assert!(!dates_are_valid(&[(1, date(2025, 9, 1))], &allocations, &weeks()));
+    }
+
+    #[test]
+    fn dates_outside_the_planned_week_are_misplaced() {
+        let dates = [exam_date(1, date(2025, 9, 9)), exam_date(1, date(2025, 9, 22))];
+
+        let report = check_evaluations(&[test(1, 3)], &dates, &weeks(), &max_pp(2));
+
+        assert!(report.valid);
+        assert_eq!(report.misplaced_dates.len(), 1);
+        assert_eq!(report.misplaced_dates[0].week, Some(1));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::asignaciones_horas;
+use crate::utils::calendar::CalendarWeek;
+use crate::utils::export::{cell_text, BalanceHeader};
+use crate::utils::totals::{ActivityType, BalanceTotals, TypeHours};
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook, XlsxError};
+
+/// Primera fila de la tabla de semanas
+const GRID_ROW: u32 = 4;
+
+/// Longitud máxima del nombre de una hoja de Excel
+const MAX_SHEET_NAME: usize = 31;
+
+/// Nombre de hoja válido para Excel a partir de la etiqueta del año: sin
+/// los caracteres `[]:*?/\`, sin comillas simples en los extremos y con
+/// 31 caracteres como máximo
+fn sheet_name(label: &str) -> String {
+    let name: String = label
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(MAX_SHEET_NAME)
+        .collect();
+    let name = name.trim().trim_matches('\'').trim();
+    if name.is_empty() {
+        "Balance".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Genera el .xlsx de un balance con el formato oficial de la facultad:
+/// cabecera institucional, tabla de semanas y tabla de cálculos con Coef.
+pub fn build_balance_xlsx(
+    header: &BalanceHeader,
+    weeks: &[CalendarWeek],
+    allocations: &[asignaciones_horas::Model],
+    totals: &BalanceTotals,
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name(&header.year_label))?;
+    sheet.set_landscape();
+
+    let title = Format::new()
+        .set_bold()
+        .set_font_size(14)
+        .set_align(FormatAlign::Center);
+    let subtitle = Format::new().set_bold().set_align(FormatAlign::Center);
+    let info = Format::new().set_italic().set_align(FormatAlign::Center);
+    let head = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_text_wrap()
+        .set_border(FormatBorder::Thin)
+        .set_background_color(Color::RGB(0xD9E1F2));
+    let cell = Format::new()
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Thin);
+    let name = Format::new().set_border(FormatBorder::Thin);
+    let total = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Thin)
+        .set_background_color(Color::RGB(0xF2F2F2));
+    let coef = Format::new()
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Thin)
+        .set_num_format("0.00");
+    let coef_total = coef.clone().set_bold().set_background_color(Color::RGB(0xF2F2F2));
+
+    let last_col = weeks.iter().map(|w| w.days.len() as u16).sum::<u16>();
+
+    // Cabecera institucional
+    sheet.merge_range(0, 0, 0, last_col, &header.faculty, &title)?;
+    sheet.merge_range(1, 0, 1, last_col, &header.title, &subtitle)?;
+    sheet.merge_range(2, 0, 2, last_col, &header.info_line(), &info)?;
+
+    // Cabecera de la tabla: semanas, fechas y días
+    sheet.merge_range(GRID_ROW, 0, GRID_ROW + 2, 0, "Asignaturas", &head)?;
+    sheet.set_column_width(0, 32)?;
+
+    let mut col: u16 = 1;
+    let mut week_cols = Vec::with_capacity(weeks.len());
+    for week in weeks {
+        let last = col + week.days.len() as u16 - 1;
+        sheet.merge_range(GRID_ROW, col, GRID_ROW, last, &week.label, &head)?;
+        let dates = format!(
+            "{} - {}",
+            week.start.format("%d/%m"),
+            week.end.format("%d/%m")
+        );
+        sheet.merge_range(GRID_ROW + 1, col, GRID_ROW + 1, last, &dates, &head)?;
+        for (i, _) in week.days.iter().enumerate() {
+            sheet.write_number_with_format(GRID_ROW + 2, col + i as u16, (i + 1) as f64, &head)?;
+            sheet.set_column_width(col + i as u16, 4)?;
+        }
+        week_cols.push((week.week, col, last));
+        col = last + 1;
+    }
+
+    // Filas de asignaturas
+    let mut row = GRID_ROW + 3;
+    for subject in &totals.subjects {
+        sheet.write_string_with_format(row, 0, &subject.name, &name)?;
+        for (week, first, last) in &week_cols {
+            let hours = allocations
+                .iter()
+                .find(|a| a.asignatura_id == subject.asignatura_id && a.week == *week)
+                .map(TypeHours::from)
+                .unwrap_or_default();
+            sheet.merge_range(row, *first, row, *last, &cell_text(&hours), &cell)?;
+        }
+        row += 1;
+    }
+
+    // Tabla de cálculos
+    row += 2;
+    let columns: Vec<&str> = ["Asignaturas", "Total"]
+        .into_iter()
+        .chain(ActivityType::ALL.iter().map(|a| a.code()))
+        .chain(["Coef."])
+        .collect();
+    for (i, label) in columns.iter().enumerate() {
+        sheet.write_string_with_format(row, i as u16, *label, &head)?;
+    }
+    row += 1;
+
+    for subject in &totals.subjects {
+        sheet.write_string_with_format(row, 0, &subject.name, &name)?;
+        sheet.write_number_with_format(row, 1, subject.total as f64, &total)?;
+        for (i, activity) in ActivityType::ALL.iter().enumerate() {
+            sheet.write_number_with_format(row, 2 + i as u16, subject.hours.get(*activity) as f64, &cell)?;
+        }
+        sheet.write_number_with_format(row, columns.len() as u16 - 1, subject.coef, &coef)?;
+        row += 1;
+    }
+
+    sheet.write_string_with_format(row, 0, "TOTAL", &total)?;
+    sheet.write_number_with_format(row, 1, totals.total as f64, &total)?;
+    for (i, activity) in ActivityType::ALL.iter().enumerate() {
+        sheet.write_number_with_format(row, 2 + i as u16, totals.hours.get(*activity) as f64, &total)?;
+    }
+    sheet.write_number_with_format(row, columns.len() as u16 - 1, totals.coef, &coef_total)?;
+
+    workbook.save_to_buffer()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_name_removes_forbidden_characters() {
+        assert_eq!(sheet_name("2do.ICS [1/2]: *?\\"), "2do.ICS 12");
+    }
+
+    #[test]
+    fn sheet_name_is_truncated_to_31_characters() {
+        let name = sheet_name("Año académico de Ingeniería Informática");
+        assert_eq!(name.chars().count(), MAX_SHEET_NAME);
+        assert!(name.starts_with("Año académico"));
+    }
+
+    #[test]
+    fn sheet_name_falls_back_when_empty() {
+        assert_eq!(sheet_name("'[]'"), "Balance");
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{balances, carreras, facultades};
+use crate::utils::totals::{ActivityType, TypeHours};
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+
+/// Facultad por defecto (balances sin carrera asignada)
+pub const FACULTY_NAME: &str = "FACULTAD DE CIBERSEGURIDAD";
+/// Título del documento
+pub const DOCUMENT_TITLE: &str = "Balance de Carga Docente";
+/// Carrera por defecto
+pub const CAREER_NAME: &str = "Ingeniería en Ciberseguridad";
+/// Sufijo por defecto de los años académicos (1ro.ICS, 2do.ICS...)
+pub const CAREER_SUFFIX: &str = "ICS";
+
+/// Facultad y carrera que aparecen en la cabecera institucional
+#[derive(Debug, Clone)]
+pub struct CareerInfo {
+    pub faculty: String,
+    pub name: String,
+    pub code: String,
+}
+
+impl Default for CareerInfo {
+    fn default() -> Self {
+        CareerInfo {
+            faculty: FACULTY_NAME.to_string(),
+            name: CAREER_NAME.to_string(),
+            code: CAREER_SUFFIX.to_string(),
+        }
+    }
+}
+
+impl CareerInfo {
+    /// Etiqueta de un año académico de la carrera, p. ej. "2do.ICS"
+    pub fn year_label(&self, academic_year: &str) -> String {
+        format!("{}.{}", academic_year, self.code)
+    }
+}
+
+/// Carga la facultad y la carrera indicadas (o las de por defecto)
+pub async fn career_info<C: ConnectionTrait>(
+    db: &C,
+    carrera_id: Option<i32>,
+) -> Result<CareerInfo, DbErr> {
+    let Some(carrera_id) = carrera_id else {
+        return Ok(CareerInfo::default());
+    };
+    let Some((career, faculty)) = carreras::Entity::find_by_id(carrera_id)
+        .find_also_related(facultades::Entity)
+        .one(db)
+        .await?
+    else {
+        return Ok(CareerInfo::default());
+    };
+
+    Ok(CareerInfo {
+        faculty: faculty
+            .map(|f| f.name.to_uppercase())
+            .unwrap_or_else(|| FACULTY_NAME.to_string()),
+        name: career.name,
+        code: career.code,
+    })
+}
+
+/// Datos de la cabecera institucional de un balance exportado
+#[derive(Debug, Clone)]
+pub struct BalanceHeader {
+    pub faculty: String,
+    pub title: String,
+    pub career: String,
+    pub year_label: String,
+    pub school_year: String,
+    pub period: String,
+}
+
+impl BalanceHeader {
+    pub fn for_balance(balance: &balances::Model, career: &CareerInfo) -> Self {
+        BalanceHeader {
+            faculty: career.faculty.clone(),
+            title: DOCUMENT_TITLE.to_string(),
+            career: career.name.clone(),
+            year_label: career.year_label(&balance.academic_year),
+            school_year: balance.school_year.clone(),
+            period: balance.period.clone(),
+        }
+    }
+
+    /// Línea de carrera, curso y período (igual que en balance.html)
+    pub fn info_line(&self) -> String {
+        format!(
+            "Carrera {} | Año: {} | Curso: {} | Período: {}",
+            self.career, self.year_label, self.school_year, self.period
+        )
+    }
+
+    /// Nombre de archivo sugerido para la descarga, sin extensión. Puede
+    /// llevar caracteres no ASCII: `content_disposition` lo adapta a la cabecera.
+    pub fn file_stem(&self) -> String {
+        format!(
+            "balance_{}_{}_{}",
+            self.year_label, self.period, self.school_year
+        )
+        .replace(['/', ' ', '.'], "_")
+    }
+}
+
+/// Reemplaza por '_' todo carácter que no sea [A-Za-z0-9_-]
+fn ascii_file_part(part: &str) -> String {
+    part.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Valor de la cabecera Content-Disposition de una descarga: `filename` con
+/// una versión ASCII segura del nombre y `filename*` con el nombre original
+/// codificado en UTF-8 (RFC 6266)
+pub fn content_disposition(file_name: &str) -> String {
+    let ascii = match file_name.rsplit_once('.') {
+        Some((stem, extension)) => {
+            format!("{}.{}", ascii_file_part(stem), ascii_file_part(extension))
+        }
+        None => ascii_file_part(file_name),
+    };
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-') {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", ascii, encoded)
+}
+
+/// Texto de una celda de la tabla: horas por tipo, p. ej. "2C 2CP"
+pub fn cell_text(hours: &TypeHours) -> String {
+    ActivityType::ALL
+        .iter()
+        .filter(|a| hours.get(**a) > 0)
+        .map(|a| format!("{}{}", hours.get(*a), a.code()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_disposition_quotes_only_safe_ascii() {
+        let value = content_disposition("balance_1er año_1ero_2024\"-2025.xlsx");
+        assert_eq!(
+            value,
+            "attachment; filename=\"balance_1er_a_o_1ero_2024_-2025.xlsx\"; \
+             filename*=UTF-8''balance_1er%20a%C3%B1o_1ero_2024%22-2025.xlsx"
+        );
+    }
+
+    #[test]
+    fn content_disposition_keeps_plain_names() {
+        assert_eq!(
+            content_disposition("reporte_balances.pdf"),
+            "attachment; filename=\"reporte_balances.pdf\"; \
+             filename*=UTF-8''reporte_balances.pdf"
+        );
+    }
+}
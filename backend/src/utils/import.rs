@@ -0,0 +1,421 @@
+use crate::sea_orm_active_enums::{AcademicYear, Semester};
+use crate::utils::totals::{
+    is_valid_week, ActivityType, TypeHours, MAX_CELL_HOURS, TOTAL_WEEKS,
+};
+use calamine::{open_workbook_from_rs, Reader, Xlsx};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// Cabecera mágica de los archivos ZIP (.xlsx)
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Fila válida del archivo de importación
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRow {
+    pub line: usize,
+    pub name: String,
+    pub year: AcademicYear,
+    pub semester: Semester,
+    pub week: Option<i32>,
+    pub hours: Option<TypeHours>,
+}
+
+/// Error de validación de una fila (o de la cabecera, línea 1)
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportError {
+    pub line: usize,
+    pub field: String,
+    pub message: String,
+}
+
+impl ImportError {
+    fn new(line: usize, field: &str, message: impl Into<String>) -> Self {
+        ImportError {
+            line,
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Resultado de leer y validar el archivo
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub rows: Vec<ImportRow>,
+    pub errors: Vec<ImportError>,
+}
+
+/// Lee un .xlsx (primera hoja) o un .csv y devuelve sus celdas como texto
+pub fn read_table(bytes: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    if bytes.starts_with(ZIP_MAGIC) {
+        let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(bytes))
+            .map_err(|e| format!("Archivo .xlsx no válido: {}", e))?;
+        let range = workbook
+            .worksheet_range_at(0)
+            .ok_or("El archivo .xlsx no tiene hojas")?
+            .map_err(|e| format!("No se pudo leer la hoja: {}", e))?;
+
+        Ok(range
+            .rows()
+            .map(|row| row.iter().map(|c| c.to_string().trim().to_string()).collect())
+            .collect())
+    } else {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(bytes);
+
+        reader
+            .records()
+            .map(|r| {
+                r.map(|record| record.iter().map(|c| c.to_string()).collect())
+                    .map_err(|e| format!("Archivo .csv no válido: {}", e))
+            })
+            .collect()
+    }
+}
+
+/// Posición de cada columna reconocida en la cabecera
+#[derive(Default)]
+struct Columns {
+    name: Option<usize>,
+    year: Option<usize>,
+    semester: Option<usize>,
+    week: Option<usize>,
+    activities: Vec<(ActivityType, usize)>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Self {
+        let mut columns = Columns::default();
+        for (i, title) in header.iter().enumerate() {
+            let title = title.trim().to_lowercase();
+            match title.as_str() {
+                "nombre" | "asignatura" | "name" => columns.name = Some(i),
+                "año" | "ano" | "year" => columns.year = Some(i),
+                "semestre" | "período" | "periodo" | "semester" => columns.semester = Some(i),
+                "semana" | "week" => columns.week = Some(i),
+                _ => {
+                    if let Some(activity) =
+                        ActivityType::ALL.iter().find(|a| a.code().eq_ignore_ascii_case(&title))
+                    {
+                        columns.activities.push((*activity, i));
+                    }
+                }
+            }
+        }
+        columns
+    }
+}
+
+/// Lee un número entero entre 0 y `max` ("2" o "2.0" desde Excel). Los
+/// valores mayores se rechazan en lugar de truncarse.
+fn parse_whole_number(value: &str, max: i32) -> Option<i32> {
+    let number: f64 = value.replace(',', ".").parse().ok()?;
+    if !(0.0..=f64::from(max)).contains(&number) || number.fract() != 0.0 {
+        return None;
+    }
+    Some(number as i32)
+}
+
+/// Valida las filas de la tabla. La primera fila debe ser la cabecera con,
+/// al menos, las columnas de nombre, año y semestre; la semana y las horas
+/// por tipo de actividad (C, CP, S, PL, TE, T, PP) son opcionales.
+pub fn parse_rows(table: &[Vec<String>]) -> ParsedImport {
+    let mut parsed = ParsedImport::default();
+
+    let Some(header) = table.first() else {
+        parsed.errors.push(ImportError::new(1, "", "El archivo está vacío"));
+        return parsed;
+    };
+
+    let columns = Columns::from_header(header);
+    let (Some(name_col), Some(year_col), Some(semester_col)) =
+        (columns.name, columns.year, columns.semester)
+    else {
+        parsed.errors.push(ImportError::new(
+            1,
+            "",
+            "La cabecera debe tener las columnas nombre, año y semestre",
+        ));
+        return parsed;
+    };
+
+    for (index, row) in table.iter().enumerate().skip(1) {
+        let line = index + 1;
+        if row.iter().all(|c| c.trim().is_empty()) {
+            continue;
+        }
+        let cell = |col: usize| row.get(col).map(|c| c.trim()).unwrap_or("");
+        let errors_before = parsed.errors.len();
+
+        let name = cell(name_col).to_string();
+        if name.is_empty() {
+            parsed.errors.push(ImportError::new(line, "nombre", "El nombre es obligatorio"));
+        }
+
+        let year = AcademicYear::parse(cell(year_col));
+        if year.is_none() {
+            parsed.errors.push(ImportError::new(
+                line,
+                "año",
+                format!("Año no válido: '{}'", cell(year_col)),
+            ));
+        }
+
+        let semester = Semester::parse(cell(semester_col));
+        if semester.is_none() {
+            parsed.errors.push(ImportError::new(
+                line,
+                "semestre",
+                format!("Semestre no válido: '{}'", cell(semester_col)),
+            ));
+        }
+
+        let mut hours = TypeHours::default();
+        let mut has_hours = false;
+        for (activity, col) in &columns.activities {
+            let value = cell(*col);
+            if value.is_empty() {
+                continue;
+            }
+            match parse_whole_number(value, MAX_CELL_HOURS) {
+                Some(h) => {
+                    has_hours = true;
+                    hours.set(*activity, h);
+                }
+                None => parsed.errors.push(ImportError::new(
+                    line,
+                    activity.code(),
+                    format!("Horas no válidas: '{}' (máximo {})", value, MAX_CELL_HOURS),
+                )),
+            }
+        }
+
+        let week = match columns.week.map(cell).filter(|v| !v.is_empty()) {
+            Some(value) => match parse_whole_number(value, TOTAL_WEEKS) {
+                Some(w) if is_valid_week(w) => Some(w),
+                _ => {
+                    parsed.errors.push(ImportError::new(
+                        line,
+                        "semana",
+                        format!("Semana no válida: '{}'", value),
+                    ));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if has_hours && week.is_none() && parsed.errors.len() == errors_before {
+            parsed.errors.push(ImportError::new(
+                line,
+                "semana",
+                "Las horas planificadas requieren indicar la semana",
+            ));
+        }
+
+        if let (Some(year), Some(semester)) = (year, semester)
+            && parsed.errors.len() == errors_before
+        {
+            parsed.rows.push(ImportRow {
+                line,
+                name,
+                year,
+                semester,
+                week,
+                hours: if has_hours { Some(hours) } else { None },
+            });
+        }
+    }
+
+    parsed
+}
+
+/// Clave de una asignatura en el catálogo: nombre (sin distinguir mayúsculas) y año
+pub fn subject_key(name: &str, year: AcademicYear) -> (String, AcademicYear) {
+    (name.trim().to_lowercase(), year)
+}
+
+/// Comprueba las filas válidas contra el catálogo y el balance de destino:
+/// la importación no cambia el semestre de una asignatura (ni la del
+/// catálogo ni entre filas del archivo), y las horas solo pueden ser de
+/// asignaturas del año y semestre del balance (`balance_period`).
+pub fn check_rows(
+    parsed: &mut ParsedImport,
+    catalog: &HashMap<(String, AcademicYear), Semester>,
+    balance_period: Option<(&str, &str)>,
+) {
+    let mut semesters: HashMap<(String, AcademicYear), Semester> = HashMap::new();
+    for row in &parsed.rows {
+        let key = subject_key(&row.name, row.year);
+        if let Some(semester) = catalog.get(&key)
+            && *semester != row.semester
+        {
+            parsed.errors.push(ImportError::new(
+                row.line,
+                "semestre",
+                format!("La asignatura ya existe en el {} semestre", semester.as_str()),
+            ));
+        } else if let Some(semester) = semesters.get(&key)
+            && *semester != row.semester
+        {
+            parsed.errors.push(ImportError::new(
+                row.line,
+                "semestre",
+                format!("La asignatura aparece antes en el {} semestre", semester.as_str()),
+            ));
+        }
+        semesters.entry(key).or_insert(row.semester);
+
+        if let Some((academic_year, period)) = balance_period
+            && row.hours.is_some()
+        {
+            if row.year.as_str() != academic_year {
+                parsed.errors.push(ImportError::new(
+                    row.line,
+                    "año",
+                    format!("El balance es de {} año", academic_year),
+                ));
+            }
+            if row.semester.as_str() != period {
+                parsed.errors.push(ImportError::new(
+                    row.line,
+                    "semestre",
+                    format!("El balance es del {} semestre", period),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|r| r.iter().map(|c| c.to_string()).collect())
+            .collect()
+    }
+
+    fn fields(parsed: &ParsedImport) -> Vec<(usize, &str)> {
+        parsed.errors.iter().map(|e| (e.line, e.field.as_str())).collect()
+    }
+
+    #[test]
+    fn subjects_and_hours_are_read() {
+        let parsed = parse_rows(&table(&[
+            &["Nombre", "Año", "Semestre", "Semana", "C", "PL"],
+            &["Matemática I", "1ro", "1ero", "3", "2", "2.0"],
+            &["", "", "", "", "", ""],
+            &["Física", "1ro", "2do", "", "", ""],
+        ]));
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.rows.len(), 2);
+        let first = &parsed.rows[0];
+        assert_eq!((first.line, first.week), (2, Some(3)));
+        assert_eq!(first.hours.map(|h| (h.c, h.pl)), Some((2, 2)));
+        assert_eq!(parsed.rows[1].line, 4);
+        assert_eq!(parsed.rows[1].hours, None);
+    }
+
+    #[test]
+    fn header_must_have_name_year_and_semester() {
+        let parsed = parse_rows(&table(&[&["Nombre", "Semana"], &["Física", "1"]]));
+
+        assert_eq!(fields(&parsed), [(1, "")]);
+        assert!(parsed.rows.is_empty());
+    }
+
+    #[test]
+    fn invalid_rows_are_reported_by_field() {
+        let parsed = parse_rows(&table(&[
+            &["Nombre", "Año", "Semestre", "Semana", "C"],
+            &["", "5to", "3ro", "", ""],
+            &["Física", "1ro", "1ero", "", "-1"],
+            &["Química", "1ro", "1ero", "", "2"],
+        ]));
+
+        assert_eq!(
+            fields(&parsed),
+            [(2, "nombre"), (2, "año"), (2, "semestre"), (3, "C"), (4, "semana")]
+        );
+        assert!(parsed.rows.is_empty());
+    }
+
+    #[test]
+    fn weeks_must_be_whole_numbers_within_the_balance() {
+        let parsed = parse_rows(&table(&[
+            &["Nombre", "Año", "Semestre", "Semana", "C"],
+            &["Física", "1ro", "1ero", "2.5", "2"],
+            &["Física", "1ro", "1ero", "20", "2"],
+            &["Física", "1ro", "1ero", "cuatro", "2"],
+            &["Física", "1ro", "1ero", "4294967297", "2"],
+            &["Física", "1ro", "1ero", "4.0", "2"],
+        ]));
+
+        assert_eq!(
+            fields(&parsed),
+            [(2, "semana"), (3, "semana"), (4, "semana"), (5, "semana")]
+        );
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.rows[0].week, Some(4));
+    }
+
+    #[test]
+    fn hours_above_the_cell_maximum_are_rejected() {
+        let parsed = parse_rows(&table(&[
+            &["Nombre", "Año", "Semestre", "Semana", "C", "PL"],
+            &["Física", "1ro", "1ero", "1", "40", "41"],
+            &["Física", "1ro", "1ero", "2", "1e12", "0"],
+            &["Física", "1ro", "1ero", "3", "40", "0"],
+        ]));
+
+        assert_eq!(fields(&parsed), [(2, "PL"), (3, "C")]);
+        assert_eq!(parsed.rows.len(), 1);
+    }
+
+    #[test]
+    fn existing_subjects_keep_their_semester() {
+        let mut parsed = parse_rows(&table(&[
+            &["Nombre", "Año", "Semestre"],
+            &["Física", "1ro", "2do"],
+            &["Química", "1ro", "1ero"],
+            &["química ", "1ro", "2do"],
+        ]));
+        let catalog = HashMap::from([(
+            subject_key("Física", AcademicYear::Primero),
+            Semester::Primero,
+        )]);
+
+        check_rows(&mut parsed, &catalog, None);
+
+        assert_eq!(fields(&parsed), [(2, "semestre"), (4, "semestre")]);
+    }
+
+    #[test]
+    fn hours_must_match_the_balance_year_and_semester() {
+        let mut parsed = parse_rows(&table(&[
+            &["Nombre", "Año", "Semestre", "Semana", "C"],
+            &["Física", "1ro", "1ero", "1", "2"],
+            &["Química", "2do", "1ero", "1", "2"],
+            &["Biología", "1ro", "2do", "1", "2"],
+            &["Historia", "3ro", "2do", "", ""],
+        ]));
+
+        check_rows(&mut parsed, &HashMap::new(), Some(("1ro", "1ero")));
+
+        assert_eq!(fields(&parsed), [(3, "año"), (4, "semestre")]);
+    }
+
+    #[test]
+    fn empty_file_is_an_error() {
+        let parsed = parse_rows(&[]);
+
+        assert_eq!(fields(&parsed), [(1, "")]);
+    }
+}
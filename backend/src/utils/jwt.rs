@@ -1,12 +1,29 @@
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Header, Validation};
 use rocket::http::Status;
 use rocket::request::{FromRequest, Outcome, Request};
-use rocket::http::CookieJar;
+use rand::RngCore;
+use rocket::State;
 use serde::{Deserialize, Serialize};
+use crate::{usuarios, AppState};
+use crate::utils::keys::key_ring;
+use crate::utils::permissions::{perm, Permission, RequiredPermission};
+use std::marker::PhantomData;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-// Clave secreta para firmar los tokens (en producción, debe estar en variables de entorno)
-const JWT_SECRET: &str = "tu_clave_secreta_muy_segura_cambiar_en_produccion";
+/// Duración de los tokens de acceso de la API si no se configura
+/// `JWT_ACCESS_EXPIRATION_MINUTES`. Son de corta duración porque el cliente
+/// los renueva con su token de renovación.
+pub const DEFAULT_ACCESS_MINUTES: usize = 15;
+
+/// Segundos de validez de un token de acceso de la API
+pub fn access_ttl_secs() -> usize {
+    std::env::var("JWT_ACCESS_EXPIRATION_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|m| *m > 0)
+        .unwrap_or(DEFAULT_ACCESS_MINUTES)
+        * 60
+}
 
 // Estructura de los claims del JWT
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,14 +31,17 @@ pub struct Claims {
     pub sub: String,      // Subject (user ID)
     pub email: String,    // Email del usuario
     pub name: String,     // Nombre del usuario
-    pub is_admin: bool,   // Si es administrador
+    #[serde(default)]
+    pub permissions: Vec<Permission>, // Permisos efectivos (única fuente de autorización)
     pub exp: usize,       // Expiration time (timestamp)
     pub iat: usize,       // Issued at (timestamp)
+    #[serde(default)]
+    pub jti: String,      // ID único del token (para revocarlo en el logout)
 }
 
 impl Claims {
     /// Crea un nuevo claim con una expiración de 24 horas
-    pub fn new(user_id: i32, email: String, name: String, is_admin: bool) -> Self {
+    pub fn new(user_id: i32, email: String, name: String, permissions: Vec<Permission>) -> Self {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards")
@@ -31,18 +51,25 @@ impl Claims {
             sub: user_id.to_string(),
             email,
             name,
-            is_admin,
+            permissions,
             iat: now,
             exp: now + 86400, // 24 horas = 86400 segundos
+            jti: new_jti(),
         }
     }
 
+    /// Crea los claims (24 horas) a partir del usuario de la base de datos y
+    /// de sus permisos efectivos (ver `permissions_for_user`)
+    pub fn from_user(user: &usuarios::Model, permissions: Vec<Permission>) -> Self {
+        Claims::new(user.id, user.email.clone(), user.name.clone(), permissions)
+    }
+
     /// Crea un token con una expiración personalizada en segundos
     pub fn with_expiration(
         user_id: i32,
         email: String,
         name: String,
-        is_admin: bool,
+        permissions: Vec<Permission>,
         expiration_secs: usize,
     ) -> Self {
         let now = SystemTime::now()
@@ -54,31 +81,55 @@ impl Claims {
             sub: user_id.to_string(),
             email,
             name,
-            is_admin,
+            permissions,
             iat: now,
             exp: now + expiration_secs,
+            jti: new_jti(),
         }
     }
+
+    /// Cambia la expiración a `expiration_secs` segundos desde la emisión
+    pub fn expires_in(mut self, expiration_secs: usize) -> Self {
+        self.exp = self.iat + expiration_secs;
+        self
+    }
+
+    /// Devuelve el ID del usuario (campo `sub`) como entero
+    pub fn user_id(&self) -> i32 {
+        self.sub.parse().unwrap_or(0)
+    }
+
+    /// Indica si el usuario tiene un permiso
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
 }
 
-/// Genera un token JWT a partir de los claims
+/// Identificador aleatorio de 128 bits para el claim `jti`
+fn new_jti() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Genera un token JWT a partir de los claims, firmado con la clave actual
+/// e identificándola en la cabecera `kid`
 pub fn create_jwt(claims: &Claims) -> Result<String, jsonwebtoken::errors::Error> {
-    let header = Header::new(Algorithm::HS256);
-    encode(
-        &header,
-        claims,
-        &EncodingKey::from_secret(JWT_SECRET.as_ref()),
-    )
+    let key = key_ring().current();
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+    encode(&header, claims, &key.encoding)
 }
 
-/// Decodifica y valida un token JWT
+/// Decodifica y valida un token JWT con la clave indicada en su `kid`
+/// (cualquier clave no retirada)
 pub fn decode_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let validation = Validation::new(Algorithm::HS256);
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(JWT_SECRET.as_ref()),
-        &validation,
-    )?;
+    let header = decode_header(token)?;
+    let key = key_ring()
+        .find(header.kid.as_deref())
+        .ok_or(jsonwebtoken::errors::ErrorKind::InvalidSignature)?;
+    let validation = Validation::new(key.algorithm);
+    let token_data = decode::<Claims>(token, &key.decoding, &validation)?;
     Ok(token_data.claims)
 }
 
@@ -99,13 +150,7 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
         let token_from_header = request
             .headers()
             .get_one("Authorization")
-            .and_then(|header| {
-                if header.starts_with("Bearer ") {
-                    Some(header[7..].to_string())
-                } else {
-                    None
-                }
-            });
+            .and_then(|header| header.strip_prefix("Bearer ").map(|t| t.to_string()));
 
         // Si no hay token en el header, intentar obtenerlo de la cookie
         let token = match token_from_header {
@@ -116,21 +161,36 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
             }
         };
 
-        match token {
-            Some(token) => match decode_jwt(&token) {
-                Ok(claims) => Outcome::Success(AuthenticatedUser(claims)),
-                Err(_) => Outcome::Error((Status::Unauthorized, ())),
-            },
-            None => Outcome::Error((Status::Unauthorized, ())),
+        let claims = match token.map(|t| decode_jwt(&t)) {
+            Some(Ok(claims)) => claims,
+            _ => return Outcome::Error((Status::Unauthorized, ())),
+        };
+
+        // Todos los tokens emitidos llevan `jti`; sin él no se podrían
+        // revocar, así que se rechazan
+        if claims.jti.is_empty() {
+            return Outcome::Error((Status::Unauthorized, ()));
+        }
+
+        // Rechazar los tokens revocados (logout) aunque no hayan caducado
+        let state = match request.guard::<&State<AppState>>().await {
+            Outcome::Success(state) => state,
+            _ => return Outcome::Error((Status::InternalServerError, ())),
+        };
+        if state.revoked.is_revoked(&state.db, &claims.jti) {
+            return Outcome::Error((Status::Unauthorized, ()));
         }
+
+        Outcome::Success(AuthenticatedUser(claims))
     }
 }
 
-/// Guardián que valida que el usuario sea administrador
-pub struct AdminUser(pub Claims);
+/// Guardián genérico que exige un permiso concreto. Las rutas declaran en su
+/// firma lo que necesitan, p. ej. `_user: Require<perm::ManageSettings>`.
+pub struct Require<P: RequiredPermission>(pub Claims, PhantomData<P>);
 
 #[rocket::async_trait]
-impl<'r> FromRequest<'r> for AdminUser {
+impl<'r, P: RequiredPermission> FromRequest<'r> for Require<P> {
     type Error = ();
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
@@ -141,15 +201,17 @@ impl<'r> FromRequest<'r> for AdminUser {
             Outcome::Forward(f) => return Outcome::Forward(f),
         };
 
-        // Verificamos que sea administrador
-        if auth_user.0.is_admin {
-            Outcome::Success(AdminUser(auth_user.0))
+        if auth_user.0.has(P::PERMISSION) {
+            Outcome::Success(Require(auth_user.0, PhantomData))
         } else {
             Outcome::Error((Status::Forbidden, ()))
         }
     }
 }
 
+/// Guardián que valida que el usuario sea administrador (gestión de usuarios)
+pub type AdminUser = Require<perm::ManageUsers>;
+
 // ============================================================================
 // RESPUESTAS JSON PARA AUTENTICACIÓN
 // ============================================================================
@@ -159,6 +221,8 @@ pub struct LoginResponse {
     pub success: bool,
     pub message: String,
     pub token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
     pub user: Option<UserInfo>,
 }
 
@@ -167,7 +231,11 @@ pub struct UserInfo {
     pub id: String,
     pub name: String,
     pub email: String,
+    /// Indicadores derivados de `permissions`, para los clientes antiguos
     pub is_admin: bool,
+    pub can_write: bool,
+    pub is_approver: bool,
+    pub permissions: Vec<Permission>,
 }
 
 impl LoginResponse {
@@ -176,11 +244,15 @@ impl LoginResponse {
             success: true,
             message: "Login exitoso".to_string(),
             token: Some(token),
+            refresh_token: None,
             user: Some(UserInfo {
                 id: claims.sub.clone(),
                 name: claims.name.clone(),
                 email: claims.email.clone(),
-                is_admin: claims.is_admin,
+                is_admin: claims.has(Permission::ManageUsers),
+                can_write: claims.has(Permission::WriteCatalog),
+                is_approver: claims.has(Permission::ApproveBalances),
+                permissions: claims.permissions.clone(),
             }),
         }
     }
@@ -190,7 +262,14 @@ impl LoginResponse {
             success: false,
             message,
             token: None,
+            refresh_token: None,
             user: None,
         }
     }
+
+    /// Añade el token de renovación a la respuesta
+    pub fn with_refresh_token(mut self, refresh_token: String) -> Self {
+        self.refresh_token = Some(refresh_token);
+        self
+    }
 }
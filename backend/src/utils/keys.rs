@@ -0,0 +1,390 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPublicKey;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// `kid` de la clave definida solo con `JWT_SECRET` (y de los tokens
+/// emitidos antes de que existiera la cabecera `kid`)
+pub const DEFAULT_KID: &str = "default";
+
+/// Clave de desarrollo, solo con `JWT_ALLOW_DEV_SECRET=true` (NO usar en
+/// producción)
+const DEVELOPMENT_SECRET: &str = "tu_clave_secreta_muy_segura_cambiar_en_produccion";
+
+/// Cabecera DER de una clave pública Ed25519 (SubjectPublicKeyInfo); le
+/// siguen los 32 bytes de la clave
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Clave pública en formato JWK (RFC 7517)
+#[derive(Debug, Clone, Serialize)]
+pub struct Jwk {
+    pub kty: &'static str,
+    #[serde(rename = "use")]
+    pub usage: &'static str,
+    pub alg: &'static str,
+    pub kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+}
+
+/// Documento de `/.well-known/jwks.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+/// Clave de firma identificada por su `kid`
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding: EncodingKey,
+    pub decoding: DecodingKey,
+    /// Clave pública publicable (solo las claves asimétricas)
+    pub jwk: Option<Jwk>,
+}
+
+impl SigningKey {
+    fn hmac(kid: &str, secret: &str) -> Self {
+        SigningKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::HS256,
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            jwk: None,
+        }
+    }
+
+    /// Pareja de claves RS256 o EdDSA a partir de sus ficheros PEM
+    fn asymmetric(
+        kid: &str,
+        algorithm: Algorithm,
+        private_pem: &[u8],
+        public_pem: &[u8],
+    ) -> Result<Self, String> {
+        let invalid = |what: &str, e: &dyn std::fmt::Display| format!("{} no válida: {}", what, e);
+        let public_str = std::str::from_utf8(public_pem).map_err(|e| invalid("Clave pública", &e))?;
+
+        let (encoding, decoding, jwk) = match algorithm {
+            Algorithm::RS256 => {
+                let public = RsaPublicKey::from_public_key_pem(public_str)
+                    .or_else(|_| RsaPublicKey::from_pkcs1_pem(public_str))
+                    .map_err(|e| invalid("Clave pública RSA", &e))?;
+                let jwk = Jwk {
+                    kty: "RSA",
+                    usage: "sig",
+                    alg: "RS256",
+                    kid: kid.to_string(),
+                    n: Some(URL_SAFE_NO_PAD.encode(public.n().to_bytes_be())),
+                    e: Some(URL_SAFE_NO_PAD.encode(public.e().to_bytes_be())),
+                    crv: None,
+                    x: None,
+                };
+                (
+                    EncodingKey::from_rsa_pem(private_pem)
+                        .map_err(|e| invalid("Clave privada RSA", &e))?,
+                    DecodingKey::from_rsa_pem(public_pem)
+                        .map_err(|e| invalid("Clave pública RSA", &e))?,
+                    jwk,
+                )
+            }
+            Algorithm::EdDSA => {
+                let der = pem::parse(public_pem).map_err(|e| invalid("Clave pública Ed25519", &e))?;
+                let key = der
+                    .contents()
+                    .strip_prefix(&ED25519_SPKI_PREFIX[..])
+                    .filter(|k| k.len() == 32)
+                    .ok_or_else(|| "La clave pública no es Ed25519".to_string())?;
+                let jwk = Jwk {
+                    kty: "OKP",
+                    usage: "sig",
+                    alg: "EdDSA",
+                    kid: kid.to_string(),
+                    n: None,
+                    e: None,
+                    crv: Some("Ed25519"),
+                    x: Some(URL_SAFE_NO_PAD.encode(key)),
+                };
+                (
+                    EncodingKey::from_ed_pem(private_pem)
+                        .map_err(|e| invalid("Clave privada Ed25519", &e))?,
+                    DecodingKey::from_ed_pem(public_pem)
+                        .map_err(|e| invalid("Clave pública Ed25519", &e))?,
+                    jwk,
+                )
+            }
+            other => return Err(format!("Algoritmo asimétrico no soportado: {:?}", other)),
+        };
+
+        Ok(SigningKey {
+            kid: kid.to_string(),
+            algorithm,
+            encoding,
+            decoding,
+            jwk: Some(jwk),
+        })
+    }
+}
+
+/// Lee un fichero PEM indicado en la configuración
+fn read_pem(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path.trim()).map_err(|e| format!("No se pudo leer {}: {}", path, e))
+}
+
+/// Claves con las que se firman y validan los tokens. Se firma siempre con
+/// la clave actual y se acepta cualquier clave no retirada, de modo que los
+/// secretos pueden rotarse sin cerrar la sesión de los usuarios.
+pub struct KeyRing {
+    current: String,
+    keys: HashMap<String, SigningKey>,
+}
+
+/// Lista separada por comas sin elementos vacíos
+fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+impl KeyRing {
+    /// Lee las claves de la configuración:
+    ///
+    /// - `JWT_KEYS`: claves activas como `kid:secreto`, separadas por comas.
+    /// - `JWT_SECRET`: una clave más con kid `default` (compatibilidad).
+    /// - `JWT_CURRENT_KID`: clave con la que se firma (obligatoria si hay
+    ///   varias).
+    /// - `JWT_RETIRED_KIDS`: claves que ya no se aceptan.
+    /// - `JWT_ALGORITHM` (`RS256` o `EdDSA`), `JWT_PRIVATE_KEY_FILE`,
+    ///   `JWT_PUBLIC_KEY_FILE` y `JWT_KEY_ID`: pareja de claves asimétricas.
+    ///   Si se configura, es la clave actual salvo que `JWT_CURRENT_KID`
+    ///   indique otra, y su clave pública se publica en el JWKS.
+    /// - `JWT_ALLOW_DEV_SECRET=true`: sin ninguna clave configurada se usa la
+    ///   clave de desarrollo; si no, la configuración es un error.
+    pub fn from_env() -> Result<Self, String> {
+        KeyRing::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Igual que `from_env`, leyendo cada variable con `lookup`
+    fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+
+        let mut keys = HashMap::new();
+        for entry in var("JWT_KEYS").map(|v| list(&v)).unwrap_or_default() {
+            let (kid, secret) = entry
+                .split_once(':')
+                .map(|(k, s)| (k.trim(), s.trim()))
+                .filter(|(k, s)| !k.is_empty() && !s.is_empty())
+                .ok_or_else(|| format!("Entrada de JWT_KEYS no válida (kid:secreto): {}", entry))?;
+            if keys.insert(kid.to_string(), SigningKey::hmac(kid, secret)).is_some() {
+                return Err(format!("kid repetido en JWT_KEYS: {}", kid));
+            }
+        }
+        if let Some(secret) = var("JWT_SECRET")
+            && keys
+                .insert(DEFAULT_KID.to_string(), SigningKey::hmac(DEFAULT_KID, secret.trim()))
+                .is_some()
+        {
+            return Err(format!("kid repetido en JWT_KEYS y JWT_SECRET: {}", DEFAULT_KID));
+        }
+
+        let mut asymmetric_kid = None;
+        if let Some(private_file) = var("JWT_PRIVATE_KEY_FILE") {
+            let algorithm = match var("JWT_ALGORITHM").as_deref().map(str::trim) {
+                Some("RS256") | None => Algorithm::RS256,
+                Some("EdDSA") => Algorithm::EdDSA,
+                Some(other) => return Err(format!("JWT_ALGORITHM no soportado: {}", other)),
+            };
+            let public_file = var("JWT_PUBLIC_KEY_FILE").ok_or_else(|| {
+                "JWT_PUBLIC_KEY_FILE es obligatoria con JWT_PRIVATE_KEY_FILE".to_string()
+            })?;
+            let kid = var("JWT_KEY_ID")
+                .map(|k| k.trim().to_string())
+                .unwrap_or_else(|| format!("{:?}", algorithm).to_lowercase());
+            let key = SigningKey::asymmetric(
+                &kid,
+                algorithm,
+                &read_pem(&private_file)?,
+                &read_pem(&public_file)?,
+            )?;
+            if keys.insert(kid.clone(), key).is_some() {
+                return Err(format!("kid repetido en JWT_KEY_ID: {}", kid));
+            }
+            asymmetric_kid = Some(kid);
+        }
+
+        if keys.is_empty() {
+            let allow_dev = var("JWT_ALLOW_DEV_SECRET").is_some_and(|v| v.trim() == "true");
+            if !allow_dev {
+                return Err("No hay ninguna clave JWT configurada (JWT_SECRET, JWT_KEYS o \
+                            JWT_PRIVATE_KEY_FILE); JWT_ALLOW_DEV_SECRET=true permite usar \
+                            la clave de desarrollo"
+                    .to_string());
+            }
+            println!("⚠️  JWT_SECRET no configurada: usando la clave de desarrollo");
+            keys.insert(DEFAULT_KID.to_string(), SigningKey::hmac(DEFAULT_KID, DEVELOPMENT_SECRET));
+        }
+
+        for kid in var("JWT_RETIRED_KIDS").map(|v| list(&v)).unwrap_or_default() {
+            keys.remove(&kid);
+        }
+
+        let current = match var("JWT_CURRENT_KID") {
+            Some(kid) => kid.trim().to_string(),
+            None if asymmetric_kid.as_ref().is_some_and(|k| keys.contains_key(k)) => {
+                asymmetric_kid.unwrap_or_default()
+            }
+            None if keys.len() == 1 => keys.keys().next().cloned().unwrap_or_default(),
+            None => return Err("JWT_CURRENT_KID es obligatoria con varias claves".to_string()),
+        };
+        if !keys.contains_key(&current) {
+            return Err(format!("La clave actual '{}' no existe o está retirada", current));
+        }
+
+        Ok(KeyRing { current, keys })
+    }
+
+    /// Clave con la que se firman los tokens nuevos
+    pub fn current(&self) -> &SigningKey {
+        &self.keys[&self.current]
+    }
+
+    /// Clave para validar un token según su cabecera `kid`. Los tokens sin
+    /// `kid` se emitieron con la clave `default` (o con la única que había).
+    pub fn find(&self, kid: Option<&str>) -> Option<&SigningKey> {
+        match kid {
+            Some(kid) => self.keys.get(kid),
+            None => self.keys.get(DEFAULT_KID).or_else(|| Some(self.current())),
+        }
+    }
+
+    /// Claves públicas de las claves asimétricas no retiradas, para que
+    /// otras aplicaciones validen los tokens sin conocer ningún secreto
+    pub fn jwks(&self) -> JwkSet {
+        let mut keys: Vec<Jwk> = self.keys.values().filter_map(|k| k.jwk.clone()).collect();
+        keys.sort_by(|a, b| a.kid.cmp(&b.kid));
+        JwkSet { keys }
+    }
+}
+
+static KEY_RING: OnceLock<KeyRing> = OnceLock::new();
+
+/// Claves de firma (se cargan de la configuración en el primer uso)
+pub fn key_ring() -> &'static KeyRing {
+    KEY_RING.get_or_init(|| {
+        dotenvy::dotenv().ok();
+        KeyRing::from_env().expect("Configuración de claves JWT no válida")
+    })
+}
+
+/// Carga las claves al arrancar; una configuración incorrecta detiene el
+/// servidor en lugar de fallar en el primer login
+pub fn init_keys() {
+    println!("🔑 Claves JWT cargadas (firma con kid '{}')", key_ring().current);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring(vars: &[(&str, &str)]) -> Result<KeyRing, String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        KeyRing::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn jwt_secret_is_the_default_key() {
+        let ring = ring(&[("JWT_SECRET", "secreto")]).unwrap();
+
+        assert_eq!(ring.current().kid, DEFAULT_KID);
+        assert_eq!(ring.current().algorithm, Algorithm::HS256);
+        assert_eq!(ring.find(None).map(|k| k.kid.as_str()), Some(DEFAULT_KID));
+        assert!(ring.jwks().keys.is_empty());
+    }
+
+    #[test]
+    fn signs_with_the_current_kid_and_accepts_the_others() {
+        let ring = ring(&[
+            ("JWT_KEYS", "2025a:anterior, 2025b:nueva"),
+            ("JWT_CURRENT_KID", "2025b"),
+        ])
+        .unwrap();
+
+        assert_eq!(ring.current().kid, "2025b");
+        assert!(ring.find(Some("2025a")).is_some());
+        assert!(ring.find(Some("otra")).is_none());
+    }
+
+    #[test]
+    fn retired_kids_are_no_longer_accepted() {
+        let ring = ring(&[
+            ("JWT_KEYS", "2025a:anterior,2025b:nueva"),
+            ("JWT_SECRET", "antiguo"),
+            ("JWT_CURRENT_KID", "2025b"),
+            ("JWT_RETIRED_KIDS", "2025a,default"),
+        ])
+        .unwrap();
+
+        assert!(ring.find(Some("2025a")).is_none());
+        assert!(ring.find(Some(DEFAULT_KID)).is_none());
+        // Los tokens sin kid se validan con la clave actual
+        assert_eq!(ring.find(None).map(|k| k.kid.as_str()), Some("2025b"));
+    }
+
+    #[test]
+    fn the_current_key_cannot_be_retired() {
+        let result = ring(&[
+            ("JWT_KEYS", "2025a:anterior,2025b:nueva"),
+            ("JWT_CURRENT_KID", "2025a"),
+            ("JWT_RETIRED_KIDS", "2025a"),
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn several_keys_require_a_current_kid() {
+        assert!(ring(&[("JWT_KEYS", "2025a:anterior,2025b:nueva")]).is_err());
+    }
+
+    #[test]
+    fn malformed_or_repeated_entries_are_rejected() {
+        assert!(ring(&[("JWT_KEYS", "sin_secreto")]).is_err());
+        assert!(ring(&[("JWT_KEYS", "a:uno,a:dos"), ("JWT_CURRENT_KID", "a")]).is_err());
+        // JWT_SECRET es la clave "default": no puede definirse también en JWT_KEYS
+        assert!(ring(&[("JWT_KEYS", "default:uno"), ("JWT_SECRET", "dos")]).is_err());
+    }
+
+    #[test]
+    fn the_development_key_must_be_allowed_explicitly() {
+        assert!(ring(&[]).is_err());
+        assert!(ring(&[("JWT_SECRET", "  ")]).is_err());
+
+        let ring = ring(&[("JWT_ALLOW_DEV_SECRET", "true")]).unwrap();
+        assert_eq!(ring.current().kid, DEFAULT_KID);
+    }
+
+    #[test]
+    fn private_key_requires_its_public_key() {
+        let result = ring(&[("JWT_PRIVATE_KEY_FILE", "jwt_private.pem")]);
+
+        assert!(result.is_err());
+    }
+}
@@ -1,2 +1,19 @@
+pub mod calendar;
+pub mod comparison;
+pub mod curriculum;
 pub mod db;
-pub mod jwt;
\ No newline at end of file
+pub mod evaluations;
+pub mod excel;
+pub mod export;
+pub mod import;
+pub mod jwt;
+pub mod keys;
+pub mod pdf;
+pub mod permissions;
+pub mod refresh;
+pub mod revisions;
+pub mod revocation;
+pub mod totals;
+pub mod validation;
+pub mod workflow;
+pub mod workload;
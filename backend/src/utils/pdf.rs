@@ -0,0 +1,360 @@
+use crate::asignaciones_horas;
+use crate::utils::calendar::{CalendarWeek, WeekKind};
+use crate::utils::export::{cell_text, BalanceHeader};
+use crate::utils::totals::{ActivityType, BalanceTotals, TypeHours};
+use printpdf::path::PaintMode;
+use printpdf::{
+    BuiltinFont, Color, Error, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Point, Rect, Rgb,
+};
+
+// A4 apaisado, medidas en milímetros
+const PAGE_WIDTH: f32 = 297.0;
+const PAGE_HEIGHT: f32 = 210.0;
+const MARGIN: f32 = 10.0;
+const FOOTER_HEIGHT: f32 = 8.0;
+const HEADER_ROW: f32 = 6.0;
+const BODY_ROW: f32 = 5.5;
+const SIGNATURE_HEIGHT: f32 = 32.0;
+const PT_TO_MM: f32 = 0.3528;
+
+/// Ancho aproximado de un texto en Helvetica (media de 0.5 em por carácter)
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * 0.5 * PT_TO_MM
+}
+
+/// Recorta el texto para que quepa en el ancho indicado
+fn fit_text(text: &str, size: f32, width: f32) -> String {
+    if text_width(text, size) <= width {
+        return text.to_string();
+    }
+    let max_chars = (width / (size * 0.5 * PT_TO_MM)) as usize;
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept)
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb(Rgb::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, None))
+}
+
+/// Texto y estilo de una celda de tabla
+struct CellStyle {
+    size: f32,
+    bold: bool,
+    fill: Option<Color>,
+    centered: bool,
+}
+
+/// Escritor de páginas A4 apaisadas con coordenadas desde la esquina superior
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layers: Vec<PdfLayerReference>,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Distancia desde el borde superior de la página actual
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Result<Self, Error> {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Capa 1");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(PdfWriter {
+            doc,
+            layers: vec![layer],
+            regular,
+            bold,
+            y: MARGIN,
+        })
+    }
+
+    fn layer(&self) -> &PdfLayerReference {
+        self.layers.last().expect("el documento siempre tiene una página")
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Capa 1");
+        self.layers.push(self.doc.get_page(page).get_layer(layer));
+        self.y = MARGIN;
+    }
+
+    /// Indica si caben `height` milímetros más en la página actual
+    fn fits(&self, height: f32) -> bool {
+        self.y + height <= PAGE_HEIGHT - MARGIN - FOOTER_HEIGHT
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, baseline: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer()
+            .use_text(text, size, Mm(x), Mm(PAGE_HEIGHT - baseline), font);
+    }
+
+    fn centered_text(&self, text: &str, size: f32, x: f32, width: f32, baseline: f32, bold: bool) {
+        let text = fit_text(text, size, width);
+        let offset = (width - text_width(&text, size)).max(0.0) / 2.0;
+        self.text(&text, size, x + offset, baseline, bold);
+    }
+
+    fn line(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.layer().add_line(Line {
+            points: vec![
+                (Point::new(Mm(x1), Mm(PAGE_HEIGHT - y1)), false),
+                (Point::new(Mm(x2), Mm(PAGE_HEIGHT - y2)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn cell(&self, x: f32, y: f32, width: f32, height: f32, text: &str, style: &CellStyle) {
+        let layer = self.layer();
+        layer.set_outline_color(rgb(0, 0, 0));
+        layer.set_outline_thickness(0.3);
+        let rect = Rect::new(
+            Mm(x),
+            Mm(PAGE_HEIGHT - y - height),
+            Mm(x + width),
+            Mm(PAGE_HEIGHT - y),
+        );
+        match &style.fill {
+            Some(color) => {
+                layer.set_fill_color(color.clone());
+                layer.add_rect(rect.with_mode(PaintMode::FillStroke));
+            }
+            None => layer.add_rect(rect.with_mode(PaintMode::Stroke)),
+        }
+        layer.set_fill_color(rgb(0, 0, 0));
+
+        let baseline = y + height / 2.0 + style.size * PT_TO_MM * 0.35;
+        if style.centered {
+            self.centered_text(text, style.size, x, width, baseline, style.bold);
+        } else {
+            let text = fit_text(text, style.size, width - 2.0);
+            self.text(&text, style.size, x + 1.0, baseline, style.bold);
+        }
+    }
+
+    /// Cabecera institucional (igual que en balance.html)
+    fn institutional_header(&mut self, faculty: &str, title: &str, info: &str) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN;
+        self.centered_text(faculty, 13.0, MARGIN, width, self.y + 5.0, true);
+        self.centered_text(title, 11.0, MARGIN, width, self.y + 11.0, true);
+        self.centered_text(info, 8.0, MARGIN, width, self.y + 16.0, false);
+        self.y += 21.0;
+    }
+
+    /// Bloque de firmas del coordinador y del jefe de departamento
+    fn signature_block(&mut self, coordinator: &str) {
+        if !self.fits(SIGNATURE_HEIGHT) {
+            self.new_page();
+        }
+        let top = self.y + 18.0;
+        let width = 80.0;
+        let blocks = [
+            (MARGIN + 25.0, "Coordinador(a) de Año", coordinator),
+            (PAGE_WIDTH - MARGIN - 25.0 - width, "Jefe(a) de Departamento", ""),
+        ];
+        for (x, role, name) in blocks {
+            self.line(x, top, x + width, top);
+            self.centered_text(role, 8.0, x, width, top + 4.0, true);
+            self.centered_text(name, 8.0, x, width, top + 8.0, false);
+            self.text("Fecha: ____ / ____ / ________", 7.0, x, top + 13.0, false);
+        }
+        self.y += SIGNATURE_HEIGHT;
+    }
+
+    /// Numera las páginas y devuelve el documento
+    fn finish(self) -> Result<Vec<u8>, Error> {
+        let total = self.layers.len();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let label = format!("Página {} de {}", i + 1, total);
+            let x = PAGE_WIDTH - MARGIN - text_width(&label, 7.0);
+            layer.use_text(label, 7.0, Mm(x), Mm(MARGIN), &self.regular);
+        }
+        self.doc.save_to_bytes()
+    }
+}
+
+/// Etiqueta corta de la columna de semana para la tabla impresa
+fn short_label(week: &CalendarWeek) -> String {
+    match week.kind {
+        WeekKind::Teaching => format!("Sem. {}", week.week),
+        WeekKind::Consultation => "Consultas".to_string(),
+        WeekKind::FinalExams => "Finales".to_string(),
+        WeekKind::ExtraI => "Extra I".to_string(),
+        WeekKind::ExtraII => "Extra II".to_string(),
+    }
+}
+
+/// Dibuja una tabla simple (cabecera + filas) paginando cuando no cabe
+fn simple_table(
+    pdf: &mut PdfWriter,
+    widths: &[f32],
+    columns: &[String],
+    rows: &[Vec<String>],
+    bold_last: bool,
+    header: &dyn Fn(&mut PdfWriter),
+) {
+    let head = CellStyle { size: 7.0, bold: true, fill: Some(rgb(0xD9, 0xE1, 0xF2)), centered: true };
+
+    let draw_columns = |pdf: &mut PdfWriter| {
+        let mut x = MARGIN;
+        for (label, width) in columns.iter().zip(widths) {
+            pdf.cell(x, pdf.y, *width, HEADER_ROW, label, &head);
+            x += width;
+        }
+        pdf.y += HEADER_ROW;
+    };
+
+    if !pdf.fits(HEADER_ROW + BODY_ROW) {
+        pdf.new_page();
+        header(pdf);
+    }
+    draw_columns(pdf);
+
+    for (index, row) in rows.iter().enumerate() {
+        if !pdf.fits(BODY_ROW) {
+            pdf.new_page();
+            header(pdf);
+            draw_columns(pdf);
+        }
+        let is_total = bold_last && index + 1 == rows.len();
+        let mut x = MARGIN;
+        for (col, (value, width)) in row.iter().zip(widths).enumerate() {
+            let style = CellStyle {
+                size: 7.0,
+                bold: is_total,
+                fill: if is_total { Some(rgb(0xF2, 0xF2, 0xF2)) } else { None },
+                centered: col > 0,
+            };
+            pdf.cell(x, pdf.y, *width, BODY_ROW, value, &style);
+            x += width;
+        }
+        pdf.y += BODY_ROW;
+    }
+}
+
+/// Filas de la tabla de cálculos (Total, C ... PP, Coef.) con la fila TOTAL
+fn calculation_rows(totals: &BalanceTotals) -> (Vec<String>, Vec<Vec<String>>) {
+    let columns: Vec<String> = ["Asignaturas", "Total"]
+        .into_iter()
+        .chain(ActivityType::ALL.iter().map(|a| a.code()))
+        .chain(["Coef."])
+        .map(|c| c.to_string())
+        .collect();
+
+    let row = |name: &str, hours: &TypeHours, total: i32, coef: f64| {
+        let mut row = vec![name.to_string(), total.to_string()];
+        row.extend(ActivityType::ALL.iter().map(|a| hours.get(*a).to_string()));
+        row.push(format!("{:.2}", coef));
+        row
+    };
+
+    let mut rows: Vec<Vec<String>> = totals
+        .subjects
+        .iter()
+        .map(|s| row(&s.name, &s.hours, s.total, s.coef))
+        .collect();
+    rows.push(row("TOTAL", &totals.hours, totals.total, totals.coef));
+
+    (columns, rows)
+}
+
+/// Genera el PDF de un balance: cabecera institucional, tabla de semanas,
+/// tabla de cálculos y bloque de firmas, en A4 apaisado
+pub fn build_balance_pdf(
+    header: &BalanceHeader,
+    weeks: &[CalendarWeek],
+    allocations: &[asignaciones_horas::Model],
+    totals: &BalanceTotals,
+    coordinator: &str,
+) -> Result<Vec<u8>, Error> {
+    let mut pdf = PdfWriter::new(&format!("{} - {}", header.title, header.year_label))?;
+    let info = header.info_line();
+    let draw_header =
+        |pdf: &mut PdfWriter| pdf.institutional_header(&header.faculty, &header.title, &info);
+
+    // Tabla de semanas: una columna por semana con las horas por tipo
+    let name_width = 50.0;
+    let week_width = (PAGE_WIDTH - 2.0 * MARGIN - name_width) / weeks.len().max(1) as f32;
+    let head = CellStyle { size: 5.5, bold: true, fill: Some(rgb(0xD9, 0xE1, 0xF2)), centered: true };
+    let body = CellStyle { size: 5.5, bold: false, fill: None, centered: true };
+    let name = CellStyle { size: 6.5, bold: false, fill: None, centered: false };
+    let reduced = CellStyle { size: 5.0, bold: false, fill: Some(rgb(0xFF, 0xF2, 0xCC)), centered: true };
+
+    let draw_grid_header = |pdf: &mut PdfWriter| {
+        let y = pdf.y;
+        pdf.cell(MARGIN, y, name_width, 2.0 * HEADER_ROW, "Asignaturas", &head);
+        for (i, week) in weeks.iter().enumerate() {
+            let x = MARGIN + name_width + i as f32 * week_width;
+            pdf.cell(x, y, week_width, HEADER_ROW, &short_label(week), &head);
+            let dates = format!("{}-{}", week.start.format("%d/%m"), week.end.format("%d/%m"));
+            let style = if week.reduced { &reduced } else { &body };
+            pdf.cell(x, y + HEADER_ROW, week_width, HEADER_ROW, &dates, style);
+        }
+        pdf.y += 2.0 * HEADER_ROW;
+    };
+
+    draw_header(&mut pdf);
+    draw_grid_header(&mut pdf);
+
+    for subject in &totals.subjects {
+        if !pdf.fits(BODY_ROW) {
+            pdf.new_page();
+            draw_header(&mut pdf);
+            draw_grid_header(&mut pdf);
+        }
+        let y = pdf.y;
+        pdf.cell(MARGIN, y, name_width, BODY_ROW, &subject.name, &name);
+        for (i, week) in weeks.iter().enumerate() {
+            let hours = allocations
+                .iter()
+                .find(|a| a.asignatura_id == subject.asignatura_id && a.week == week.week)
+                .map(TypeHours::from)
+                .unwrap_or_default();
+            let x = MARGIN + name_width + i as f32 * week_width;
+            pdf.cell(x, y, week_width, BODY_ROW, &cell_text(&hours), &body);
+        }
+        pdf.y += BODY_ROW;
+    }
+
+    // Tabla de cálculos
+    pdf.y += 6.0;
+    let (columns, rows) = calculation_rows(totals);
+    let mut widths = vec![70.0];
+    widths.extend(std::iter::repeat_n(18.0, columns.len() - 1));
+    simple_table(&mut pdf, &widths, &columns, &rows, true, &draw_header);
+
+    pdf.signature_block(coordinator);
+    pdf.finish()
+}
+
+/// Genera un reporte tabular (p. ej. el resumen del dashboard) con la misma
+/// cabecera institucional que el balance impreso
+pub fn build_report_pdf(
+    faculty: &str,
+    title: &str,
+    info: &str,
+    columns: &[String],
+    rows: &[Vec<String>],
+    coordinator: &str,
+) -> Result<Vec<u8>, Error> {
+    let mut pdf = PdfWriter::new(title)?;
+    let draw_header = |pdf: &mut PdfWriter| pdf.institutional_header(faculty, title, info);
+
+    let available = PAGE_WIDTH - 2.0 * MARGIN;
+    let first = (available * 0.25).min(70.0);
+    let rest = (available - first) / (columns.len().saturating_sub(1).max(1)) as f32;
+    let mut widths = vec![first];
+    widths.extend(std::iter::repeat_n(rest.min(24.0), columns.len().saturating_sub(1)));
+
+    draw_header(&mut pdf);
+    simple_table(&mut pdf, &widths, columns, rows, false, &draw_header);
+
+    pdf.y += 6.0;
+    pdf.signature_block(coordinator);
+    pdf.finish()
+}
@@ -0,0 +1,119 @@
+use crate::{roles_permisos, usuarios};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+/// Permisos que pueden concederse a un rol. El código es el que se guarda en
+/// la tabla `permisos` y el que viaja en los claims del JWT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Permission {
+    #[serde(rename = "balances.view_all")]
+    ViewAllBalances,
+    #[serde(rename = "balances.edit_all")]
+    EditAllBalances,
+    #[serde(rename = "balances.approve")]
+    ApproveBalances,
+    #[serde(rename = "catalog.write")]
+    WriteCatalog,
+    #[serde(rename = "academic.manage")]
+    ManageAcademic,
+    #[serde(rename = "professors.manage")]
+    ManageProfessors,
+    #[serde(rename = "professors.view_load")]
+    ViewProfessorLoad,
+    #[serde(rename = "settings.manage")]
+    ManageSettings,
+    #[serde(rename = "users.manage")]
+    ManageUsers,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 9] = [
+        Permission::ViewAllBalances,
+        Permission::EditAllBalances,
+        Permission::ApproveBalances,
+        Permission::WriteCatalog,
+        Permission::ManageAcademic,
+        Permission::ManageProfessors,
+        Permission::ViewProfessorLoad,
+        Permission::ManageSettings,
+        Permission::ManageUsers,
+    ];
+
+    /// Código del permiso en la base de datos
+    pub fn code(&self) -> &'static str {
+        match self {
+            Permission::ViewAllBalances => "balances.view_all",
+            Permission::EditAllBalances => "balances.edit_all",
+            Permission::ApproveBalances => "balances.approve",
+            Permission::WriteCatalog => "catalog.write",
+            Permission::ManageAcademic => "academic.manage",
+            Permission::ManageProfessors => "professors.manage",
+            Permission::ViewProfessorLoad => "professors.view_load",
+            Permission::ManageSettings => "settings.manage",
+            Permission::ManageUsers => "users.manage",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Permission::ALL.into_iter().find(|p| p.code() == code.trim())
+    }
+}
+
+/// Permiso exigido por el guardián `Require<P>`
+pub trait RequiredPermission: Send + Sync + 'static {
+    const PERMISSION: Permission;
+}
+
+/// Tipos marcador para declarar en la firma de una ruta el permiso que
+/// necesita, p. ej. `Require<perm::ApproveBalances>`
+pub mod perm {
+    use super::{Permission, RequiredPermission};
+
+    macro_rules! markers {
+        ($($name:ident),* $(,)?) => {
+            $(
+                pub struct $name;
+
+                impl RequiredPermission for $name {
+                    const PERMISSION: Permission = Permission::$name;
+                }
+            )*
+        };
+    }
+
+    markers!(
+        ViewAllBalances,
+        EditAllBalances,
+        ApproveBalances,
+        WriteCatalog,
+        ManageAcademic,
+        ManageProfessors,
+        ViewProfessorLoad,
+        ManageSettings,
+        ManageUsers,
+    );
+}
+
+/// Permisos efectivos del usuario: los de su rol. Un usuario sin rol no
+/// tiene ningún permiso.
+pub async fn permissions_for_user<C: ConnectionTrait>(
+    db: &C,
+    user: &usuarios::Model,
+) -> Result<Vec<Permission>, DbErr> {
+    let Some(rol_id) = user.rol_id else {
+        return Ok(Vec::new());
+    };
+
+    let granted = roles_permisos::Entity::find()
+        .filter(roles_permisos::Column::RolId.eq(rol_id))
+        .all(db)
+        .await?;
+    let mut permissions: Vec<Permission> = granted
+        .iter()
+        .filter_map(|g| Permission::parse(&g.permiso_code))
+        .collect();
+
+    permissions.sort();
+    permissions.dedup();
+    Ok(permissions)
+}
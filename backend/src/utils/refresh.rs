@@ -0,0 +1,151 @@
+use crate::tokens_renovacion;
+use chrono::{Duration, NaiveDateTime};
+use rand::RngCore;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set};
+use sha2::{Digest, Sha256};
+
+/// Duración de un token de renovación si no se configura `JWT_REFRESH_EXPIRATION_DAYS`
+pub const DEFAULT_REFRESH_DAYS: i64 = 7;
+
+/// Días de validez de un token de renovación
+pub fn refresh_ttl_days() -> i64 {
+    std::env::var("JWT_REFRESH_EXPIRATION_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|d| *d > 0)
+        .unwrap_or(DEFAULT_REFRESH_DAYS)
+}
+
+/// Valor aleatorio de 256 bits en hexadecimal
+fn random_hex() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Hash SHA-256 (hexadecimal) con el que se guarda el token
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[derive(Debug)]
+pub enum RefreshError {
+    /// El token no existe
+    Invalid,
+    /// El token caducó
+    Expired,
+    /// El token ya se había usado (o su familia estaba revocada):
+    /// se revoca la familia completa
+    Reused,
+    Db(DbErr),
+}
+
+impl From<DbErr> for RefreshError {
+    fn from(err: DbErr) -> Self {
+        RefreshError::Db(err)
+    }
+}
+
+fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// Emite un token de renovación para el usuario. Sin familia se inicia una
+/// nueva (inicio de sesión); con familia es la rotación de un token anterior.
+/// Devuelve el token en claro, que solo conoce el cliente.
+pub async fn issue_refresh_token<C: ConnectionTrait>(
+    db: &C,
+    usuario_id: i32,
+    family: Option<String>,
+) -> Result<String, DbErr> {
+    let token = random_hex();
+    let created_at = now();
+
+    tokens_renovacion::ActiveModel {
+        usuario_id: Set(usuario_id),
+        family: Set(family.unwrap_or_else(random_hex)),
+        token_hash: Set(hash_token(&token)),
+        expires_at: Set(created_at + Duration::days(refresh_ttl_days())),
+        created_at: Set(created_at),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(token)
+}
+
+/// Revoca todos los tokens pendientes de una familia
+pub async fn revoke_family<C: ConnectionTrait>(db: &C, family: &str) -> Result<(), DbErr> {
+    tokens_renovacion::Entity::update_many()
+        .col_expr(tokens_renovacion::Column::RevokedAt, Expr::value(now()))
+        .filter(tokens_renovacion::Column::Family.eq(family))
+        .filter(tokens_renovacion::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Consume un token de renovación y emite el siguiente de la familia.
+/// Devuelve el ID del usuario y el nuevo token en claro.
+///
+/// Presentar un token ya usado indica que se ha filtrado: se revoca la
+/// familia completa, de modo que ni el atacante ni el cliente legítimo
+/// pueden seguir renovando y el usuario debe iniciar sesión de nuevo.
+///
+/// Debe llamarse dentro de una transacción, que se confirma también con
+/// `RefreshError::Reused` para que la revocación de la familia persista.
+pub async fn rotate_refresh_token<C: ConnectionTrait>(
+    db: &C,
+    token: &str,
+) -> Result<(i32, String), RefreshError> {
+    let stored = tokens_renovacion::Entity::find()
+        .filter(tokens_renovacion::Column::TokenHash.eq(hash_token(token)))
+        .one(db)
+        .await?
+        .ok_or(RefreshError::Invalid)?;
+
+    if stored.used_at.is_some() || stored.revoked_at.is_some() {
+        revoke_family(db, &stored.family).await?;
+        return Err(RefreshError::Reused);
+    }
+    if stored.expires_at <= now() {
+        return Err(RefreshError::Expired);
+    }
+
+    // Se marca como usado solo si nadie lo hizo antes (dos peticiones
+    // simultáneas con el mismo token cuentan como reutilización)
+    let marked = tokens_renovacion::Entity::update_many()
+        .col_expr(tokens_renovacion::Column::UsedAt, Expr::value(now()))
+        .filter(tokens_renovacion::Column::Id.eq(stored.id))
+        .filter(tokens_renovacion::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+    if marked.rows_affected != 1 {
+        revoke_family(db, &stored.family).await?;
+        return Err(RefreshError::Reused);
+    }
+
+    let next = issue_refresh_token(db, stored.usuario_id, Some(stored.family)).await?;
+    Ok((stored.usuario_id, next))
+}
+
+/// Revoca la familia de un token de renovación del usuario (logout).
+/// Los tokens desconocidos o de otro usuario se ignoran.
+pub async fn revoke_refresh_token<C: ConnectionTrait>(
+    db: &C,
+    usuario_id: i32,
+    token: &str,
+) -> Result<(), DbErr> {
+    let stored = tokens_renovacion::Entity::find()
+        .filter(tokens_renovacion::Column::TokenHash.eq(hash_token(token)))
+        .filter(tokens_renovacion::Column::UsuarioId.eq(usuario_id))
+        .one(db)
+        .await?;
+
+    match stored {
+        Some(stored) => revoke_family(db, &stored.family).await,
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,332 @@
+use crate::{asignaciones_horas, balance_revisiones, balances, grupos, grupos_anio};
+use crate::utils::totals::{AllocationCell, TypeHours};
+use chrono::NaiveDate;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Copia completa de un balance en el momento de guardarlo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub academic_year: String,
+    pub period: String,
+    pub school_year: String,
+    pub start_date: NaiveDate,
+    pub allocations: Vec<AllocationCell>,
+    /// Grupos propios del balance (None en las revisiones anteriores a
+    /// guardarlos; vacío si usa los del año)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<GroupSnapshot>>,
+}
+
+/// Grupo de estudiantes y número de subgrupos de laboratorio
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupSnapshot {
+    pub name: String,
+    pub subgroups: i32,
+}
+
+impl From<&grupos::Model> for GroupSnapshot {
+    fn from(g: &grupos::Model) -> Self {
+        GroupSnapshot {
+            name: g.name.clone(),
+            subgroups: g.subgroups,
+        }
+    }
+}
+
+impl From<&grupos_anio::Model> for GroupSnapshot {
+    fn from(g: &grupos_anio::Model) -> Self {
+        GroupSnapshot {
+            name: g.name.clone(),
+            subgroups: g.subgroups,
+        }
+    }
+}
+
+/// Grupos como texto para el diff, p. ej. "A (2), B (1)"
+fn describe_groups(groups: &[GroupSnapshot]) -> String {
+    groups
+        .iter()
+        .map(|g| format!("{} ({})", g.name, g.subgroups))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl BalanceSnapshot {
+    /// Lee la copia guardada en una revisión
+    pub fn from_revision(revision: &balance_revisiones::Model) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(revision.snapshot.clone())
+    }
+}
+
+/// Toma una copia del estado actual del balance y de sus horas
+pub async fn take_snapshot<C: ConnectionTrait>(
+    db: &C,
+    balance: &balances::Model,
+) -> Result<BalanceSnapshot, DbErr> {
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .order_by_asc(asignaciones_horas::Column::AsignaturaId)
+        .order_by_asc(asignaciones_horas::Column::Week)
+        .all(db)
+        .await?;
+    let groups = grupos::Entity::find()
+        .filter(grupos::Column::BalanceId.eq(balance.id))
+        .order_by_asc(grupos::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(BalanceSnapshot {
+        academic_year: balance.academic_year.clone(),
+        period: balance.period.clone(),
+        school_year: balance.school_year.clone(),
+        start_date: balance.start_date,
+        allocations: allocations.iter().map(AllocationCell::from).collect(),
+        groups: Some(groups.iter().map(GroupSnapshot::from).collect()),
+    })
+}
+
+/// Registra una nueva revisión inmutable con el estado actual del balance.
+/// Debe llamarse dentro de la misma transacción que el guardado.
+pub async fn record_revision<C: ConnectionTrait>(
+    db: &C,
+    balance: &balances::Model,
+    user_id: i32,
+) -> Result<balance_revisiones::Model, DbErr> {
+    // Bloquea la fila del balance hasta el final de la transacción para que
+    // dos guardados simultáneos no calculen el mismo número de revisión
+    balances::Entity::find_by_id(balance.id)
+        .lock_exclusive()
+        .one(db)
+        .await?;
+
+    let last = balance_revisiones::Entity::find()
+        .filter(balance_revisiones::Column::BalanceId.eq(balance.id))
+        .order_by_desc(balance_revisiones::Column::Revision)
+        .one(db)
+        .await?;
+
+    let snapshot = take_snapshot(db, balance).await?;
+    let snapshot = serde_json::to_value(&snapshot).map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    balance_revisiones::ActiveModel {
+        balance_id: Set(balance.id),
+        revision: Set(last.map(|r| r.revision + 1).unwrap_or(1)),
+        user_id: Set(user_id),
+        created_at: Set(chrono::Local::now().naive_local()),
+        snapshot: Set(snapshot),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+/// Cambio en un dato general del balance
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// Cambio en una celda (None = celda sin horas)
+#[derive(Debug, Clone, Serialize)]
+pub struct CellChange {
+    pub asignatura_id: i32,
+    pub week: i32,
+    pub before: Option<TypeHours>,
+    pub after: Option<TypeHours>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDiff {
+    pub from: i32,
+    pub to: i32,
+    pub fields: Vec<FieldChange>,
+    pub cells: Vec<CellChange>,
+}
+
+/// Compara dos copias del balance celda a celda
+pub fn diff_snapshots(
+    from_revision: i32,
+    from: &BalanceSnapshot,
+    to_revision: i32,
+    to: &BalanceSnapshot,
+) -> RevisionDiff {
+    let mut fields = Vec::new();
+    let pairs = [
+        ("academic_year", &from.academic_year, &to.academic_year),
+        ("period", &from.period, &to.period),
+        ("school_year", &from.school_year, &to.school_year),
+    ];
+    for (field, before, after) in pairs {
+        if before != after {
+            fields.push(FieldChange {
+                field,
+                before: before.clone(),
+                after: after.clone(),
+            });
+        }
+    }
+    if from.start_date != to.start_date {
+        fields.push(FieldChange {
+            field: "start_date",
+            before: from.start_date.to_string(),
+            after: to.start_date.to_string(),
+        });
+    }
+    if let (Some(before), Some(after)) = (&from.groups, &to.groups)
+        && before != after
+    {
+        fields.push(FieldChange {
+            field: "groups",
+            before: describe_groups(before),
+            after: describe_groups(after),
+        });
+    }
+
+    let index = |s: &BalanceSnapshot| -> BTreeMap<(i32, i32), TypeHours> {
+        s.allocations
+            .iter()
+            .map(|c| ((c.asignatura_id, c.week), c.hours))
+            .collect()
+    };
+    let before = index(from);
+    let after = index(to);
+
+    let mut keys: Vec<&(i32, i32)> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let cells = keys
+        .into_iter()
+        .filter(|k| before.get(k) != after.get(k))
+        .map(|k| CellChange {
+            asignatura_id: k.0,
+            week: k.1,
+            before: before.get(k).copied(),
+            after: after.get(k).copied(),
+        })
+        .collect();
+
+    RevisionDiff {
+        from: from_revision,
+        to: to_revision,
+        fields,
+        cells,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(allocations: Vec<AllocationCell>) -> BalanceSnapshot {
+        BalanceSnapshot {
+            academic_year: "2do".to_string(),
+            period: "1ero".to_string(),
+            school_year: "2025-2026".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2025, 9, 8).unwrap(),
+            allocations,
+            groups: None,
+        }
+    }
+
+    fn cell(asignatura_id: i32, week: i32, c: i32) -> AllocationCell {
+        AllocationCell {
+            asignatura_id,
+            week,
+            hours: TypeHours { c, ..Default::default() },
+        }
+    }
+
+    fn group(name: &str, subgroups: i32) -> GroupSnapshot {
+        GroupSnapshot {
+            name: name.to_string(),
+            subgroups,
+        }
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let a = snapshot(vec![cell(1, 1, 2)]);
+
+        let diff = diff_snapshots(1, &a, 2, &a.clone());
+
+        assert_eq!((diff.from, diff.to), (1, 2));
+        assert!(diff.fields.is_empty());
+        assert!(diff.cells.is_empty());
+    }
+
+    #[test]
+    fn cells_are_compared_one_by_one() {
+        let before = snapshot(vec![cell(1, 1, 2), cell(1, 2, 2), cell(2, 1, 4)]);
+        let after = snapshot(vec![cell(1, 1, 2), cell(1, 2, 4), cell(3, 5, 2)]);
+
+        let diff = diff_snapshots(1, &before, 2, &after);
+
+        let changes: Vec<(i32, i32, Option<i32>, Option<i32>)> = diff
+            .cells
+            .iter()
+            .map(|c| {
+                (c.asignatura_id, c.week, c.before.map(|h| h.c), c.after.map(|h| h.c))
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (1, 2, Some(2), Some(4)),
+                (2, 1, Some(4), None),
+                (3, 5, None, Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn general_fields_and_groups_are_compared() {
+        let mut before = snapshot(Vec::new());
+        before.groups = Some(vec![group("A", 1)]);
+        let mut after = before.clone();
+        after.school_year = "2026-2027".to_string();
+        after.start_date = NaiveDate::from_ymd_opt(2026, 9, 7).unwrap();
+        after.groups = Some(vec![group("A", 2), group("B", 1)]);
+
+        let diff = diff_snapshots(3, &before, 4, &after);
+
+        let fields: Vec<&str> = diff.fields.iter().map(|f| f.field).collect();
+        assert_eq!(fields, ["school_year", "start_date", "groups"]);
+        assert_eq!(diff.fields[2].before, "A (1)");
+        assert_eq!(diff.fields[2].after, "A (2), B (1)");
+    }
+
+    #[test]
+    fn revisions_without_groups_do_not_report_group_changes() {
+        let before = snapshot(Vec::new());
+        let mut after = before.clone();
+        after.groups = Some(vec![group("A", 2)]);
+
+        let diff = diff_snapshots(1, &before, 2, &after);
+
+        assert!(diff.fields.is_empty());
+    }
+
+    #[test]
+    fn old_snapshots_without_groups_can_be_read() {
+        let json = serde_json::json!({
+            "academic_year": "2do",
+            "period": "1ero",
+            "school_year": "2025-2026",
+            "start_date": "2025-09-08",
+            "allocations": [{ "asignatura_id": 1, "week": 1, "c": 2 }]
+        });
+
+        let snapshot: BalanceSnapshot = serde_json::from_value(json).unwrap();
+
+        assert_eq!(snapshot.groups, None);
+        assert_eq!(snapshot.allocations, [cell(1, 1, 2)]);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::tokens_revocados;
+use crate::utils::jwt::Claims;
+use chrono::NaiveDateTime;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Cada cuánto se vuelve a leer la tabla de tokens revocados, para ver las
+/// revocaciones hechas por otras instancias del servidor
+pub const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Cache {
+    /// `jti` revocados que aún no han caducado, con su expiración
+    revoked: HashMap<String, NaiveDateTime>,
+    loaded_at: Option<Instant>,
+}
+
+/// Tokens revocados antes de caducar. La tabla `tokens_revocados` es la
+/// fuente de verdad; la caché en memoria evita consultarla en cada petición.
+/// La caché se recarga en segundo plano: las peticiones nunca esperan a la
+/// base de datos y, si la recarga falla, se sigue usando la última copia.
+#[derive(Default)]
+pub struct RevocationStore {
+    cache: Arc<RwLock<Cache>>,
+    /// Hay una recarga en curso
+    reloading: Arc<AtomicBool>,
+}
+
+fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// Fecha local de expiración a partir del claim `exp`
+fn expiration_of(claims: &Claims) -> NaiveDateTime {
+    chrono::DateTime::from_timestamp(claims.exp as i64, 0)
+        .map(|d| d.with_timezone(&chrono::Local).naive_local())
+        .unwrap_or_else(now)
+}
+
+impl RevocationStore {
+    /// Carga la caché al arrancar, para no aceptar tokens revocados mientras
+    /// se hace la primera recarga en segundo plano
+    pub async fn load(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        reload(&self.cache, db).await
+    }
+
+    /// Indica si el token fue revocado según la caché. Si es antigua, lanza
+    /// una recarga en segundo plano y responde con la copia actual.
+    pub fn is_revoked(&self, db: &DatabaseConnection, jti: &str) -> bool {
+        let stale = self
+            .cache
+            .read()
+            .map(|c| c.loaded_at.is_none_or(|t| t.elapsed() >= RELOAD_INTERVAL))
+            .unwrap_or(true);
+        if stale && !self.reloading.swap(true, Ordering::AcqRel) {
+            let cache = Arc::clone(&self.cache);
+            let reloading = Arc::clone(&self.reloading);
+            let db = db.clone();
+            tokio::spawn(async move {
+                if let Err(e) = reload(&cache, &db).await {
+                    eprintln!("⚠️  No se pudieron recargar los tokens revocados: {}", e);
+                }
+                reloading.store(false, Ordering::Release);
+            });
+        }
+
+        self.cache
+            .read()
+            .map(|c| c.revoked.contains_key(jti))
+            .unwrap_or(false)
+    }
+
+    /// Revoca el token de los claims hasta su expiración
+    pub async fn revoke(&self, db: &DatabaseConnection, claims: &Claims) -> Result<(), DbErr> {
+        if claims.jti.is_empty() {
+            return Ok(());
+        }
+        let expires_at = expiration_of(claims);
+
+        tokens_revocados::Entity::insert(tokens_revocados::ActiveModel {
+            jti: Set(claims.jti.clone()),
+            usuario_id: Set(claims.user_id()),
+            expires_at: Set(expires_at),
+            revoked_at: Set(now()),
+        })
+        .on_conflict(
+            OnConflict::column(tokens_revocados::Column::Jti)
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+
+        if let Ok(mut cache) = self.cache.write() {
+            cache.revoked.insert(claims.jti.clone(), expires_at);
+        }
+        Ok(())
+    }
+}
+
+/// Borra los tokens ya caducados (no hace falta recordarlos) y vuelve a
+/// cargar el resto en memoria. Las revocaciones locales que aún no caducan
+/// se conservan aunque la lectura no las incluya (pudieron hacerse mientras
+/// se leía la tabla). Si falla, la caché queda como estaba.
+async fn reload(cache: &RwLock<Cache>, db: &DatabaseConnection) -> Result<(), DbErr> {
+    tokens_revocados::Entity::delete_many()
+        .filter(tokens_revocados::Column::ExpiresAt.lt(now()))
+        .exec(db)
+        .await?;
+
+    let stored = tokens_revocados::Entity::find().all(db).await?;
+
+    if let Ok(mut cache) = cache.write() {
+        let now = now();
+        cache.revoked.retain(|_, expires_at| *expires_at > now);
+        cache
+            .revoked
+            .extend(stored.into_iter().map(|t| (t.jti, t.expires_at)));
+        cache.loaded_at = Some(Instant::now());
+    }
+    Ok(())
+}
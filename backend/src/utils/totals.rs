@@ -0,0 +1,652 @@
+use crate::{asignaciones_horas, asignaturas, balances, grupos, grupos_anio, horas_plan};
+use crate::utils::revisions::GroupSnapshot;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+// ============================================================================
+// SEMANAS DEL BALANCE
+// ============================================================================
+
+/// Semanas lectivas (Semana 1 - Semana 15)
+pub const TEACHING_WEEKS: i32 = 15;
+/// Semana de consultas
+pub const CONSULTATION_WEEK: i32 = 16;
+/// Exámenes finales
+pub const FINAL_EXAMS_WEEK: i32 = 17;
+/// Exámenes extraordinarios I
+pub const EXTRA_I_WEEK: i32 = 18;
+/// Exámenes extraordinarios II
+pub const EXTRA_II_WEEK: i32 = 19;
+/// Total de columnas de semana en la tabla del balance
+pub const TOTAL_WEEKS: i32 = 19;
+
+/// Indica si el número de semana es válido para una asignación
+pub fn is_valid_week(week: i32) -> bool {
+    (1..=TOTAL_WEEKS).contains(&week)
+}
+
+// ============================================================================
+// TIPOS DE ACTIVIDAD Y HORAS
+// ============================================================================
+
+/// Tipos de actividad de la tabla de cálculos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ActivityType {
+    C,
+    CP,
+    S,
+    PL,
+    TE,
+    T,
+    PP,
+}
+
+impl ActivityType {
+    pub const ALL: [ActivityType; 7] = [
+        ActivityType::C,
+        ActivityType::CP,
+        ActivityType::S,
+        ActivityType::PL,
+        ActivityType::TE,
+        ActivityType::T,
+        ActivityType::PP,
+    ];
+
+    /// Código tal y como aparece en la cabecera de la tabla
+    pub fn code(&self) -> &'static str {
+        match self {
+            ActivityType::C => "C",
+            ActivityType::CP => "CP",
+            ActivityType::S => "S",
+            ActivityType::PL => "PL",
+            ActivityType::TE => "TE",
+            ActivityType::T => "T",
+            ActivityType::PP => "PP",
+        }
+    }
+
+    /// Tipo de actividad a partir de su código (sin distinguir mayúsculas)
+    pub fn parse(code: &str) -> Option<Self> {
+        ActivityType::ALL
+            .into_iter()
+            .find(|a| a.code().eq_ignore_ascii_case(code.trim()))
+    }
+}
+
+/// Máximo de horas de un mismo tipo de actividad en una celda (asignatura y
+/// semana)
+pub const MAX_CELL_HOURS: i32 = 40;
+
+/// Horas desglosadas por tipo de actividad
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeHours {
+    #[serde(default)]
+    pub c: i32,
+    #[serde(default)]
+    pub cp: i32,
+    #[serde(default)]
+    pub s: i32,
+    #[serde(default)]
+    pub pl: i32,
+    #[serde(default)]
+    pub te: i32,
+    #[serde(default)]
+    pub t: i32,
+    #[serde(default)]
+    pub pp: i32,
+}
+
+impl TypeHours {
+    /// Suma de todas las horas
+    pub fn total(&self) -> i32 {
+        self.c + self.cp + self.s + self.pl + self.te + self.t + self.pp
+    }
+
+    /// Horas de un tipo de actividad concreto
+    pub fn get(&self, activity: ActivityType) -> i32 {
+        match activity {
+            ActivityType::C => self.c,
+            ActivityType::CP => self.cp,
+            ActivityType::S => self.s,
+            ActivityType::PL => self.pl,
+            ActivityType::TE => self.te,
+            ActivityType::T => self.t,
+            ActivityType::PP => self.pp,
+        }
+    }
+
+    /// Asigna las horas de un tipo de actividad concreto
+    pub fn set(&mut self, activity: ActivityType, hours: i32) {
+        match activity {
+            ActivityType::C => self.c = hours,
+            ActivityType::CP => self.cp = hours,
+            ActivityType::S => self.s = hours,
+            ActivityType::PL => self.pl = hours,
+            ActivityType::TE => self.te = hours,
+            ActivityType::T => self.t = hours,
+            ActivityType::PP => self.pp = hours,
+        }
+    }
+
+    /// Indica si todas las horas son cero
+    pub fn is_empty(&self) -> bool {
+        ActivityType::ALL.iter().all(|a| self.get(*a) == 0)
+    }
+
+    /// Indica si alguna de las horas es negativa
+    pub fn has_negative(&self) -> bool {
+        ActivityType::ALL.iter().any(|a| self.get(*a) < 0)
+    }
+
+    /// Indica si alguna de las horas supera el máximo de una celda
+    pub fn exceeds_cell_max(&self) -> bool {
+        ActivityType::ALL.iter().any(|a| self.get(*a) > MAX_CELL_HOURS)
+    }
+}
+
+impl AddAssign for TypeHours {
+    fn add_assign(&mut self, other: Self) {
+        self.c += other.c;
+        self.cp += other.cp;
+        self.s += other.s;
+        self.pl += other.pl;
+        self.te += other.te;
+        self.t += other.t;
+        self.pp += other.pp;
+    }
+}
+
+impl From<&asignaciones_horas::Model> for TypeHours {
+    fn from(a: &asignaciones_horas::Model) -> Self {
+        TypeHours {
+            c: a.c,
+            cp: a.cp,
+            s: a.s,
+            pl: a.pl,
+            te: a.te,
+            t: a.t,
+            pp: a.pp,
+        }
+    }
+}
+
+impl From<&horas_plan::Model> for TypeHours {
+    fn from(h: &horas_plan::Model) -> Self {
+        TypeHours {
+            c: h.c,
+            cp: h.cp,
+            s: h.s,
+            pl: h.pl,
+            te: h.te,
+            t: h.t,
+            pp: h.pp,
+        }
+    }
+}
+
+/// Celda de la tabla del balance: horas de una asignatura en una semana
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocationCell {
+    pub asignatura_id: i32,
+    pub week: i32,
+    #[serde(flatten)]
+    pub hours: TypeHours,
+}
+
+/// Indica si alguna asignatura aparece más de una vez en la misma semana
+/// (la tabla tiene una sola celda por asignatura y semana)
+pub fn has_duplicate_cells(cells: &[AllocationCell]) -> bool {
+    let mut seen = std::collections::HashSet::new();
+    !cells.iter().all(|c| seen.insert((c.asignatura_id, c.week)))
+}
+
+impl From<&asignaciones_horas::Model> for AllocationCell {
+    fn from(a: &asignaciones_horas::Model) -> Self {
+        AllocationCell {
+            asignatura_id: a.asignatura_id,
+            week: a.week,
+            hours: TypeHours::from(a),
+        }
+    }
+}
+
+// ============================================================================
+// GRUPOS Y SUBGRUPOS
+// ============================================================================
+
+/// Grupos del año de un balance. Determina cuántas veces imparte el
+/// profesorado cada actividad: las conferencias (C) se dan a todo el año a
+/// la vez, las prácticas de laboratorio (PL) a cada subgrupo y el resto de
+/// actividades a cada grupo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GroupLayout {
+    pub groups: i32,
+    pub subgroups: i32,
+}
+
+impl Default for GroupLayout {
+    /// Sin grupos registrados se considera un único grupo sin dividir
+    fn default() -> Self {
+        GroupLayout {
+            groups: 1,
+            subgroups: 1,
+        }
+    }
+}
+
+impl GroupLayout {
+    pub fn from_groups(groups: &[GroupSnapshot]) -> Self {
+        if groups.is_empty() {
+            return GroupLayout::default();
+        }
+        GroupLayout {
+            groups: groups.len() as i32,
+            subgroups: groups.iter().map(|g| g.subgroups.max(1)).sum(),
+        }
+    }
+
+    /// Veces que se repite una actividad para cubrir a todos los estudiantes
+    pub fn repetitions(&self, activity: ActivityType) -> i32 {
+        match activity {
+            ActivityType::C => 1,
+            ActivityType::PL => self.subgroups,
+            _ => self.groups,
+        }
+    }
+
+    /// Horas que imparte el profesorado a partir de las horas que recibe
+    /// cada estudiante
+    pub fn professor_hours(&self, hours: &TypeHours) -> TypeHours {
+        let mut result = TypeHours::default();
+        for activity in ActivityType::ALL {
+            result.set(activity, hours.get(activity) * self.repetitions(activity));
+        }
+        result
+    }
+}
+
+// ============================================================================
+// MOTOR DE TOTALES ("Calcular Totales")
+// ============================================================================
+
+/// Fila de la tabla de cálculos para una asignatura. `hours` son las horas
+/// que recibe cada estudiante; `professor_hours`, las que imparte el
+/// profesorado para todos los grupos.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectTotals {
+    pub asignatura_id: i32,
+    pub name: String,
+    pub hours: TypeHours,
+    pub total: i32,
+    pub coef: f64,
+    pub professor_hours: TypeHours,
+    pub professor_total: i32,
+}
+
+/// Total de horas de una columna de semana
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekTotals {
+    pub week: i32,
+    pub hours: TypeHours,
+    pub total: i32,
+    pub professor_hours: TypeHours,
+    pub professor_total: i32,
+}
+
+/// Resultado completo de "Calcular Totales"
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceTotals {
+    pub subjects: Vec<SubjectTotals>,
+    pub weeks: Vec<WeekTotals>,
+    pub hours: TypeHours,
+    pub total: i32,
+    pub coef: f64,
+    pub groups: GroupLayout,
+    pub professor_hours: TypeHours,
+    pub professor_total: i32,
+}
+
+/// Coeficiente: horas promedio por semana lectiva, redondeado a 2 decimales
+pub fn coefficient(total: i32) -> f64 {
+    (total as f64 / TEACHING_WEEKS as f64 * 100.0).round() / 100.0
+}
+
+/// Calcula los totales por tipo, por asignatura, por semana y el coeficiente,
+/// tanto para los estudiantes como para el profesorado según los grupos.
+/// Las filas siguen el orden de `subjects`; se ignoran asignaciones de
+/// asignaturas que no estén en la lista.
+pub fn compute_totals(
+    subjects: &[asignaturas::Model],
+    allocations: &[asignaciones_horas::Model],
+    layout: GroupLayout,
+) -> BalanceTotals {
+    let mut per_subject: BTreeMap<i32, TypeHours> = BTreeMap::new();
+    let mut per_week: BTreeMap<i32, TypeHours> = (1..=TOTAL_WEEKS)
+        .map(|w| (w, TypeHours::default()))
+        .collect();
+    let mut hours = TypeHours::default();
+
+    for allocation in allocations {
+        if !subjects.iter().any(|s| s.id == allocation.asignatura_id) {
+            continue;
+        }
+        let cell = TypeHours::from(allocation);
+        *per_subject.entry(allocation.asignatura_id).or_default() += cell;
+        *per_week.entry(allocation.week).or_default() += cell;
+        hours += cell;
+    }
+
+    let subjects = subjects
+        .iter()
+        .map(|subject| {
+            let hours = per_subject.get(&subject.id).copied().unwrap_or_default();
+            let professor_hours = layout.professor_hours(&hours);
+            SubjectTotals {
+                asignatura_id: subject.id,
+                name: subject.name.clone(),
+                hours,
+                total: hours.total(),
+                coef: coefficient(hours.total()),
+                professor_hours,
+                professor_total: professor_hours.total(),
+            }
+        })
+        .collect();
+
+    let weeks = per_week
+        .into_iter()
+        .map(|(week, hours)| {
+            let professor_hours = layout.professor_hours(&hours);
+            WeekTotals {
+                week,
+                hours,
+                total: hours.total(),
+                professor_hours,
+                professor_total: professor_hours.total(),
+            }
+        })
+        .collect();
+
+    let professor_hours = layout.professor_hours(&hours);
+    BalanceTotals {
+        subjects,
+        weeks,
+        hours,
+        total: hours.total(),
+        coef: coefficient(hours.total()),
+        groups: layout,
+        professor_hours,
+        professor_total: professor_hours.total(),
+    }
+}
+
+/// Grupos que se aplican a un balance: los suyos propios si los tiene o, si
+/// no, los del año académico de su carrera
+pub fn effective_groups(
+    balance: &balances::Model,
+    own: &[grupos::Model],
+    year: &[grupos_anio::Model],
+) -> Vec<GroupSnapshot> {
+    let own: Vec<GroupSnapshot> = own
+        .iter()
+        .filter(|g| g.balance_id == balance.id)
+        .map(GroupSnapshot::from)
+        .collect();
+    if !own.is_empty() {
+        return own;
+    }
+    year.iter()
+        .filter(|g| {
+            Some(g.carrera_id) == balance.carrera_id && g.academic_year == balance.academic_year
+        })
+        .map(GroupSnapshot::from)
+        .collect()
+}
+
+/// Carga los grupos que se aplican a un balance (ver `effective_groups`)
+pub async fn balance_groups<C: ConnectionTrait>(
+    db: &C,
+    balance: &balances::Model,
+) -> Result<Vec<GroupSnapshot>, DbErr> {
+    let own = grupos::Entity::find()
+        .filter(grupos::Column::BalanceId.eq(balance.id))
+        .order_by_asc(grupos::Column::Name)
+        .all(db)
+        .await?;
+    let year = match balance.carrera_id {
+        Some(carrera_id) => {
+            grupos_anio::Entity::find()
+                .filter(grupos_anio::Column::CarreraId.eq(carrera_id))
+                .filter(grupos_anio::Column::AcademicYear.eq(balance.academic_year.clone()))
+                .order_by_asc(grupos_anio::Column::Name)
+                .all(db)
+                .await?
+        }
+        None => Vec::new(),
+    };
+    Ok(effective_groups(balance, &own, &year))
+}
+
+/// Grupos que se aplican a un balance, para el cálculo de totales
+pub async fn group_layout(
+    db: &DatabaseConnection,
+    balance: &balances::Model,
+) -> Result<GroupLayout, DbErr> {
+    Ok(GroupLayout::from_groups(&balance_groups(db, balance).await?))
+}
+
+/// Carga las asignaciones y los grupos de un balance y calcula sus totales
+pub async fn totals_for_balance(
+    db: &DatabaseConnection,
+    balance: &balances::Model,
+) -> Result<BalanceTotals, DbErr> {
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.eq(balance.id))
+        .all(db)
+        .await?;
+
+    let subject_ids: Vec<i32> = allocations.iter().map(|a| a.asignatura_id).collect();
+    let subjects = asignaturas::Entity::find()
+        .filter(asignaturas::Column::Id.is_in(subject_ids))
+        .order_by_asc(asignaturas::Column::Name)
+        .all(db)
+        .await?;
+
+    let layout = group_layout(db, balance).await?;
+
+    Ok(compute_totals(&subjects, &allocations, layout))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::sea_orm_active_enums::{AcademicYear, Semester};
+
+    /// Asignatura de 2do año, 1er semestre
+    pub(crate) fn subject(id: i32, name: &str) -> asignaturas::Model {
+        asignaturas::Model {
+            id,
+            name: name.to_string(),
+            year: AcademicYear::Segundo,
+            semester: Semester::Primero,
+            carrera_id: None,
+        }
+    }
+
+    /// Celda del balance 1 con las horas indicadas
+    pub(crate) fn allocation(
+        asignatura_id: i32,
+        week: i32,
+        hours: TypeHours,
+    ) -> asignaciones_horas::Model {
+        asignaciones_horas::Model {
+            id: 0,
+            balance_id: 1,
+            asignatura_id,
+            week,
+            c: hours.c,
+            cp: hours.cp,
+            s: hours.s,
+            pl: hours.pl,
+            te: hours.te,
+            t: hours.t,
+            pp: hours.pp,
+        }
+    }
+
+    fn cell(asignatura_id: i32, week: i32) -> AllocationCell {
+        AllocationCell {
+            asignatura_id,
+            week,
+            hours: TypeHours { c: 2, ..Default::default() },
+        }
+    }
+
+    fn group(name: &str, subgroups: i32) -> GroupSnapshot {
+        GroupSnapshot {
+            name: name.to_string(),
+            subgroups,
+        }
+    }
+
+    fn balance(id: i32, carrera_id: Option<i32>) -> balances::Model {
+        balances::Model {
+            id,
+            user_id: 1,
+            academic_year: "2do".to_string(),
+            period: "1ero".to_string(),
+            school_year: "2025-2026".to_string(),
+            start_date: chrono::NaiveDate::from_ymd_opt(2025, 9, 8).unwrap(),
+            status: "borrador".to_string(),
+            carrera_id,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn own_group(balance_id: i32, name: &str) -> grupos::Model {
+        grupos::Model {
+            id: 0,
+            balance_id,
+            name: name.to_string(),
+            subgroups: 1,
+        }
+    }
+
+    fn year_group(carrera_id: i32, academic_year: &str, name: &str) -> grupos_anio::Model {
+        grupos_anio::Model {
+            id: 0,
+            carrera_id,
+            academic_year: academic_year.to_string(),
+            name: name.to_string(),
+            subgroups: 2,
+        }
+    }
+
+    #[test]
+    fn totals_by_subject_week_and_type() {
+        let subjects = [subject(1, "Matemática"), subject(2, "Programación")];
+        let allocations = [
+            allocation(1, 1, TypeHours { c: 2, cp: 2, ..Default::default() }),
+            allocation(1, 2, TypeHours { c: 2, pp: 1, ..Default::default() }),
+            allocation(2, 1, TypeHours { pl: 4, ..Default::default() }),
+        ];
+
+        let totals = compute_totals(&subjects, &allocations, GroupLayout::default());
+
+        assert_eq!(totals.subjects[0].total, 7);
+        assert_eq!(totals.subjects[1].hours.pl, 4);
+        assert_eq!(totals.weeks.len(), TOTAL_WEEKS as usize);
+        assert_eq!(totals.weeks[0].total, 8);
+        assert_eq!(totals.weeks[1].total, 3);
+        assert_eq!(totals.hours.c, 4);
+        assert_eq!(totals.total, 11);
+        assert_eq!(totals.coef, 0.73);
+        assert_eq!(totals.professor_total, totals.total);
+    }
+
+    #[test]
+    fn allocations_of_unlisted_subjects_are_ignored() {
+        let subjects = [subject(1, "Matemática")];
+        let allocations = [
+            allocation(1, 1, TypeHours { c: 2, ..Default::default() }),
+            allocation(9, 1, TypeHours { c: 6, ..Default::default() }),
+        ];
+
+        let totals = compute_totals(&subjects, &allocations, GroupLayout::default());
+
+        assert_eq!(totals.subjects.len(), 1);
+        assert_eq!(totals.total, 2);
+    }
+
+    #[test]
+    fn only_balance_weeks_are_valid() {
+        assert!(is_valid_week(1));
+        assert!(is_valid_week(EXTRA_II_WEEK));
+        assert!(!is_valid_week(0));
+        assert!(!is_valid_week(TOTAL_WEEKS + 1));
+    }
+
+    #[test]
+    fn cell_hours_are_bounded_per_type() {
+        let mut hours = TypeHours { c: MAX_CELL_HOURS, pl: MAX_CELL_HOURS, ..Default::default() };
+        assert!(!hours.exceeds_cell_max());
+
+        hours.set(ActivityType::PP, MAX_CELL_HOURS + 1);
+        assert!(hours.exceeds_cell_max());
+    }
+
+    #[test]
+    fn layout_counts_groups_and_subgroups() {
+        let layout = GroupLayout::from_groups(&[group("A", 2), group("B", 3)]);
+        assert_eq!(layout, GroupLayout { groups: 2, subgroups: 5 });
+        assert_eq!(GroupLayout::from_groups(&[]), GroupLayout::default());
+    }
+
+    #[test]
+    fn balances_use_the_groups_of_their_year_unless_they_have_their_own() {
+        let year = [
+            year_group(1, "2do", "A"),
+            year_group(1, "3ro", "B"),
+            year_group(2, "2do", "C"),
+        ];
+        let own = [own_group(2, "X")];
+
+        assert_eq!(effective_groups(&balance(1, Some(1)), &own, &year), [group("A", 2)]);
+        assert_eq!(effective_groups(&balance(2, Some(1)), &own, &year), [group("X", 1)]);
+        assert!(effective_groups(&balance(3, None), &own, &year).is_empty());
+    }
+
+    #[test]
+    fn pl_is_repeated_per_subgroup_and_lectures_once() {
+        let subjects = [subject(1, "Redes")];
+        let allocations = [allocation(
+            1,
+            1,
+            TypeHours { c: 2, cp: 2, pl: 2, ..Default::default() },
+        )];
+        let layout = GroupLayout::from_groups(&[group("A", 2), group("B", 2)]);
+
+        let totals = compute_totals(&subjects, &allocations, layout);
+
+        // Cada estudiante recibe las mismas horas
+        assert_eq!(totals.total, 6);
+        // El profesorado: C una vez, CP por grupo y PL por subgrupo
+        assert_eq!(totals.professor_hours.c, 2);
+        assert_eq!(totals.professor_hours.cp, 4);
+        assert_eq!(totals.professor_hours.pl, 8);
+        assert_eq!(totals.professor_total, 14);
+        assert_eq!(totals.subjects[0].professor_total, 14);
+        assert_eq!(totals.weeks[0].professor_total, 14);
+    }
+
+    #[test]
+    fn duplicate_cells_are_detected() {
+        assert!(!has_duplicate_cells(&[cell(1, 1), cell(1, 2), cell(2, 1)]));
+        assert!(has_duplicate_cells(&[cell(1, 1), cell(2, 1), cell(1, 1)]));
+    }
+}
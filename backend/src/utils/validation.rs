@@ -0,0 +1,317 @@
+use crate::{asignaciones_horas, umbrales_carga};
+use crate::utils::calendar::CalendarWeek;
+use crate::utils::totals::TypeHours;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Máximo de horas lectivas por semana si el año no tiene umbral configurado
+pub const DEFAULT_MAX_WEEKLY_HOURS: i32 = 24;
+/// Máximo de pruebas parciales (PP) por semana si el año no tiene umbral configurado
+pub const DEFAULT_MAX_WEEKLY_PP: i32 = 2;
+
+/// Umbrales aplicados a un balance
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Thresholds {
+    pub max_weekly_hours: i32,
+    pub max_weekly_pp: i32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            max_weekly_hours: DEFAULT_MAX_WEEKLY_HOURS,
+            max_weekly_pp: DEFAULT_MAX_WEEKLY_PP,
+        }
+    }
+}
+
+impl From<&umbrales_carga::Model> for Thresholds {
+    fn from(m: &umbrales_carga::Model) -> Self {
+        Thresholds {
+            max_weekly_hours: m.max_weekly_hours,
+            max_weekly_pp: m.max_weekly_pp,
+        }
+    }
+}
+
+/// Umbrales configurados para un año académico (o los valores por defecto)
+pub async fn thresholds_for(
+    db: &DatabaseConnection,
+    academic_year: &str,
+) -> Result<Thresholds, DbErr> {
+    Ok(umbrales_carga::Entity::find_by_id(academic_year.to_string())
+        .one(db)
+        .await?
+        .map(|m| Thresholds::from(&m))
+        .unwrap_or_default())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Problema detectado en una celda (asignatura + semana) o en una semana completa
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub rule: &'static str,
+    pub week: i32,
+    pub asignatura_id: Option<i32>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub thresholds: Thresholds,
+    pub issues: Vec<Issue>,
+}
+
+/// Asignaturas con pruebas parciales (PP) en cada semana. Es el recuento que
+/// comparan con `max_weekly_pp` tanto la validación como el calendario de
+/// evaluaciones.
+pub fn tests_per_week(allocations: &[asignaciones_horas::Model]) -> BTreeMap<i32, BTreeSet<i32>> {
+    let mut tests: BTreeMap<i32, BTreeSet<i32>> = BTreeMap::new();
+    for cell in allocations.iter().filter(|a| a.pp > 0) {
+        tests.entry(cell.week).or_default().insert(cell.asignatura_id);
+    }
+    tests
+}
+
+/// Horas lectivas de una celda (todo salvo las pruebas parciales)
+fn teaching_hours(hours: &TypeHours) -> i32 {
+    hours.total() - hours.pp
+}
+
+/// Valida un balance contra las reglas de carga:
+///
+/// - `max_weekly_hours`: horas lectivas de la semana por encima del máximo
+///   (error) o por encima de la capacidad reducida de una semana que perdió
+///   días (aviso).
+/// - `max_weekly_pp`: más pruebas parciales en la semana que las permitidas.
+/// - `lecture_in_exam_week`: conferencias (C) en semanas de exámenes.
+pub fn validate_balance(
+    allocations: &[asignaciones_horas::Model],
+    weeks: &[CalendarWeek],
+    thresholds: &Thresholds,
+) -> ValidationReport {
+    let mut issues = Vec::new();
+    let tests = tests_per_week(allocations);
+
+    let mut per_week: BTreeMap<i32, Vec<&asignaciones_horas::Model>> = BTreeMap::new();
+    for allocation in allocations {
+        per_week.entry(allocation.week).or_default().push(allocation);
+    }
+
+    for (week_number, cells) in &per_week {
+        let week = weeks.iter().find(|w| w.week == *week_number);
+        let hours: i32 = cells.iter().map(|a| teaching_hours(&TypeHours::from(*a))).sum();
+
+        if hours > thresholds.max_weekly_hours {
+            issues.push(Issue {
+                severity: Severity::Error,
+                rule: "max_weekly_hours",
+                week: *week_number,
+                asignatura_id: None,
+                message: format!(
+                    "{} horas lectivas en la semana (máximo {})",
+                    hours, thresholds.max_weekly_hours
+                ),
+            });
+        } else if let Some(week) = week.filter(|w| w.reduced) {
+            let capacity =
+                (thresholds.max_weekly_hours as f64 * week.capacity_factor()).floor() as i32;
+            if hours > capacity {
+                issues.push(Issue {
+                    severity: Severity::Warning,
+                    rule: "max_weekly_hours",
+                    week: *week_number,
+                    asignatura_id: None,
+                    message: format!(
+                        "{} horas lectivas en una semana con {} de {} días disponibles (capacidad {})",
+                        hours,
+                        week.available_days,
+                        week.days.len(),
+                        capacity
+                    ),
+                });
+            }
+        }
+
+        let week_tests = tests.get(week_number).cloned().unwrap_or_default();
+        if week_tests.len() as i32 > thresholds.max_weekly_pp {
+            for asignatura_id in week_tests {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    rule: "max_weekly_pp",
+                    week: *week_number,
+                    asignatura_id: Some(asignatura_id),
+                    message: format!(
+                        "La semana tiene más de {} pruebas parciales",
+                        thresholds.max_weekly_pp
+                    ),
+                });
+            }
+        }
+
+        if week.is_some_and(|w| w.kind.is_exam()) {
+            for cell in cells.iter().filter(|a| a.c > 0) {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    rule: "lecture_in_exam_week",
+                    week: *week_number,
+                    asignatura_id: Some(cell.asignatura_id),
+                    message: "No se pueden planificar conferencias en semanas de exámenes"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+
+    ValidationReport {
+        valid: errors == 0,
+        errors,
+        warnings,
+        thresholds: *thresholds,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dias_no_lectivos;
+    use crate::utils::calendar::generate_calendar;
+    use crate::utils::totals::tests::allocation;
+    use chrono::NaiveDate;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn weeks() -> Vec<CalendarWeek> {
+        // Feriado el miércoles de la semana 5
+        let holiday = dias_no_lectivos::Model {
+            id: 0,
+            description: "Feriado".to_string(),
+            kind: "feriado".to_string(),
+            start_date: date(2025, 10, 8),
+            end_date: date(2025, 10, 8),
+            created_at: None,
+        };
+        generate_calendar(date(2025, 9, 8), &[], &[holiday])
+    }
+
+    fn thresholds() -> Thresholds {
+        Thresholds {
+            max_weekly_hours: 10,
+            max_weekly_pp: 1,
+        }
+    }
+
+    fn rules(report: &ValidationReport) -> Vec<(&'static str, Severity, i32)> {
+        report.issues.iter().map(|i| (i.rule, i.severity, i.week)).collect()
+    }
+
+    #[test]
+    fn a_balance_within_limits_is_valid() {
+        let allocations = [
+            allocation(1, 1, TypeHours { c: 4, cp: 2, pp: 1, ..Default::default() }),
+            allocation(2, 1, TypeHours { c: 4, ..Default::default() }),
+        ];
+
+        let report = validate_balance(&allocations, &weeks(), &thresholds());
+
+        assert!(report.valid);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn too_many_hours_in_a_week_is_an_error() {
+        let allocations = [
+            allocation(1, 2, TypeHours { c: 6, ..Default::default() }),
+            allocation(2, 2, TypeHours { cp: 6, ..Default::default() }),
+        ];
+
+        let report = validate_balance(&allocations, &weeks(), &thresholds());
+
+        assert!(!report.valid);
+        assert_eq!(rules(&report), [("max_weekly_hours", Severity::Error, 2)]);
+    }
+
+    #[test]
+    fn a_reduced_week_over_its_capacity_is_a_warning() {
+        // Semana 5 con 3 de 4 días: capacidad 7 de 10 horas
+        let allocations = [allocation(1, 5, TypeHours { c: 8, ..Default::default() })];
+
+        let report = validate_balance(&allocations, &weeks(), &thresholds());
+
+        assert!(report.valid);
+        assert_eq!(report.warnings, 1);
+        assert_eq!(rules(&report), [("max_weekly_hours", Severity::Warning, 5)]);
+    }
+
+    #[test]
+    fn partial_tests_do_not_count_as_teaching_hours() {
+        let allocations = [allocation(1, 3, TypeHours { c: 10, pp: 1, ..Default::default() })];
+
+        let report = validate_balance(&allocations, &weeks(), &thresholds());
+
+        assert!(report.valid);
+    }
+
+    #[test]
+    fn too_many_partial_tests_flag_every_subject() {
+        let allocations = [
+            allocation(1, 4, TypeHours { pp: 1, ..Default::default() }),
+            allocation(2, 4, TypeHours { pp: 2, ..Default::default() }),
+            allocation(3, 4, TypeHours { c: 2, ..Default::default() }),
+        ];
+
+        let report = validate_balance(&allocations, &weeks(), &thresholds());
+
+        let flagged: Vec<Option<i32>> = report
+            .issues
+            .iter()
+            .filter(|i| i.rule == "max_weekly_pp")
+            .map(|i| i.asignatura_id)
+            .collect();
+        assert_eq!(flagged, [Some(1), Some(2)]);
+        assert_eq!(report.errors, 2);
+    }
+
+    #[test]
+    fn lectures_are_not_allowed_in_exam_weeks() {
+        let allocations = [
+            allocation(1, 17, TypeHours { c: 2, ..Default::default() }),
+            allocation(2, 16, TypeHours { c: 2, ..Default::default() }),
+        ];
+
+        let report = validate_balance(&allocations, &weeks(), &thresholds());
+
+        assert_eq!(rules(&report), [("lecture_in_exam_week", Severity::Error, 17)]);
+    }
+
+    #[test]
+    fn partial_tests_are_counted_per_subject_and_week() {
+        let allocations = [
+            allocation(1, 4, TypeHours { pp: 2, ..Default::default() }),
+            allocation(2, 4, TypeHours { pp: 1, ..Default::default() }),
+            allocation(2, 6, TypeHours { c: 2, ..Default::default() }),
+        ];
+
+        let tests = tests_per_week(&allocations);
+
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[&4].iter().copied().collect::<Vec<_>>(), [1, 2]);
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+/// Estados de un balance en el flujo de aprobación
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceStatus {
+    Borrador,
+    Enviado,
+    Aprobado,
+    Rechazado,
+}
+
+impl BalanceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BalanceStatus::Borrador => "borrador",
+            BalanceStatus::Enviado => "enviado",
+            BalanceStatus::Aprobado => "aprobado",
+            BalanceStatus::Rechazado => "rechazado",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "borrador" => Some(BalanceStatus::Borrador),
+            "enviado" => Some(BalanceStatus::Enviado),
+            "aprobado" => Some(BalanceStatus::Aprobado),
+            "rechazado" => Some(BalanceStatus::Rechazado),
+            _ => None,
+        }
+    }
+
+    /// Los balances aprobados son de solo lectura hasta que se reabren
+    pub fn is_editable(&self) -> bool {
+        !matches!(self, BalanceStatus::Aprobado)
+    }
+}
+
+/// Acciones del flujo de aprobación
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// El coordinador envía el balance a revisión
+    Submit,
+    /// El Jefe de Colectivo aprueba el balance
+    Approve,
+    /// El Jefe de Colectivo rechaza el balance (con comentario)
+    Reject,
+    /// El Jefe de Colectivo reabre un balance aprobado para editarlo
+    Reopen,
+}
+
+impl Transition {
+    /// Estado resultante de aplicar la acción, o None si no está permitida
+    pub fn apply(&self, from: BalanceStatus) -> Option<BalanceStatus> {
+        use BalanceStatus::*;
+        match (self, from) {
+            (Transition::Submit, Borrador | Rechazado) => Some(Enviado),
+            (Transition::Approve, Enviado) => Some(Aprobado),
+            (Transition::Reject, Enviado) => Some(Rechazado),
+            (Transition::Reopen, Aprobado | Rechazado) => Some(Borrador),
+            _ => None,
+        }
+    }
+
+    /// Indica si la acción exige un comentario
+    pub fn requires_comment(&self) -> bool {
+        matches!(self, Transition::Reject)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BalanceStatus::*;
+    use super::*;
+
+    const ALL_STATUSES: [BalanceStatus; 4] = [Borrador, Enviado, Aprobado, Rechazado];
+
+    #[test]
+    fn allowed_transitions() {
+        assert_eq!(Transition::Submit.apply(Borrador), Some(Enviado));
+        assert_eq!(Transition::Submit.apply(Rechazado), Some(Enviado));
+        assert_eq!(Transition::Approve.apply(Enviado), Some(Aprobado));
+        assert_eq!(Transition::Reject.apply(Enviado), Some(Rechazado));
+        assert_eq!(Transition::Reopen.apply(Aprobado), Some(Borrador));
+        assert_eq!(Transition::Reopen.apply(Rechazado), Some(Borrador));
+    }
+
+    #[test]
+    fn illegal_transitions_are_rejected() {
+        assert_eq!(Transition::Submit.apply(Enviado), None);
+        assert_eq!(Transition::Submit.apply(Aprobado), None);
+        assert_eq!(Transition::Reopen.apply(Borrador), None);
+        assert_eq!(Transition::Reopen.apply(Enviado), None);
+        for from in [Borrador, Aprobado, Rechazado] {
+            assert_eq!(Transition::Approve.apply(from), None);
+            assert_eq!(Transition::Reject.apply(from), None);
+        }
+    }
+
+    #[test]
+    fn only_approved_balances_are_read_only() {
+        for status in ALL_STATUSES {
+            assert_eq!(status.is_editable(), status != Aprobado);
+        }
+    }
+
+    #[test]
+    fn status_codes_round_trip() {
+        for status in ALL_STATUSES {
+            assert_eq!(BalanceStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(BalanceStatus::parse("archivado"), None);
+    }
+
+    #[test]
+    fn only_rejection_requires_a_comment() {
+        assert!(Transition::Reject.requires_comment());
+        assert!(!Transition::Approve.requires_comment());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{asignaciones_horas, asignaciones_profesores, balances, grupos, grupos_anio, profesores};
+use crate::utils::revisions::GroupSnapshot;
+use crate::utils::totals::{effective_groups, ActivityType, TypeHours, TOTAL_WEEKS};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Carga de un profesor en una semana
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekLoad {
+    pub week: i32,
+    pub hours: TypeHours,
+    pub total: i32,
+    pub overloaded: bool,
+}
+
+/// Carga semanal de un profesor en todos los balances de un período
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfessorLoad {
+    pub profesor_id: i32,
+    pub name: String,
+    pub max_weekly_hours: i32,
+    pub weeks: Vec<WeekLoad>,
+    pub total: i32,
+    pub overloaded_weeks: Vec<i32>,
+}
+
+/// Calcula la carga semanal de cada profesor.
+///
+/// Cada asignación (asignatura, tipo de actividad, grupo) suma al profesor
+/// las horas de ese tipo que el balance planifica para la asignatura en cada
+/// semana; un profesor con dos grupos de la misma asignatura cuenta las horas
+/// dos veces. Las conferencias (C) se imparten a todo el año, por lo que
+/// solo cuentan una vez por asignatura, y las prácticas de laboratorio (PL)
+/// se repiten en cada subgrupo del grupo. `groups` son los grupos que se
+/// aplican a cada balance.
+///
+/// Si varios balances del período planifican la misma asignatura (p. ej. un
+/// balance duplicado), cada (asignatura, semana, tipo, grupo) se cuenta una
+/// sola vez, con las horas del balance que más planifica.
+pub fn compute_loads(
+    professors: &[profesores::Model],
+    assignments: &[asignaciones_profesores::Model],
+    allocations: &[asignaciones_horas::Model],
+    groups: &HashMap<i32, Vec<GroupSnapshot>>,
+) -> Vec<ProfessorLoad> {
+    // Subgrupos de cada grupo por balance
+    let subgroups: HashMap<(i32, &str), i32> = groups
+        .iter()
+        .flat_map(|(balance_id, groups)| {
+            groups
+                .iter()
+                .map(move |g| ((*balance_id, g.name.as_str()), g.subgroups.max(1)))
+        })
+        .collect();
+
+    professors
+        .iter()
+        .map(|professor| {
+            let mut per_week: BTreeMap<i32, TypeHours> = (1..=TOTAL_WEEKS)
+                .map(|w| (w, TypeHours::default()))
+                .collect();
+
+            let mut lectures: HashSet<i32> = HashSet::new();
+            for assignment in assignments.iter().filter(|a| a.profesor_id == professor.id) {
+                let Some(activity) = ActivityType::parse(&assignment.activity) else {
+                    continue;
+                };
+                if activity == ActivityType::C && !lectures.insert(assignment.asignatura_id) {
+                    continue;
+                }
+
+                // Horas de la asignación en cada semana, sin sumar balances
+                let mut assigned: BTreeMap<i32, i32> = BTreeMap::new();
+                for allocation in allocations
+                    .iter()
+                    .filter(|a| a.asignatura_id == assignment.asignatura_id)
+                {
+                    let repetitions = match activity {
+                        ActivityType::PL => subgroups
+                            .get(&(allocation.balance_id, assignment.group_name.as_str()))
+                            .copied()
+                            .unwrap_or(1),
+                        _ => 1,
+                    };
+                    let planned = TypeHours::from(allocation).get(activity) * repetitions;
+                    let week = assigned.entry(allocation.week).or_default();
+                    *week = (*week).max(planned);
+                }
+
+                for (week, planned) in assigned {
+                    let hours = per_week.entry(week).or_default();
+                    hours.set(activity, hours.get(activity) + planned);
+                }
+            }
+
+            let weeks: Vec<WeekLoad> = per_week
+                .into_iter()
+                .map(|(week, hours)| WeekLoad {
+                    week,
+                    hours,
+                    total: hours.total(),
+                    overloaded: hours.total() > professor.max_weekly_hours,
+                })
+                .collect();
+
+            ProfessorLoad {
+                profesor_id: professor.id,
+                name: professor.name.clone(),
+                max_weekly_hours: professor.max_weekly_hours,
+                total: weeks.iter().map(|w| w.total).sum(),
+                overloaded_weeks: weeks.iter().filter(|w| w.overloaded).map(|w| w.week).collect(),
+                weeks,
+            }
+        })
+        .collect()
+}
+
+/// Carga semanal de los profesores indicados (o de todos) en los balances de
+/// un curso y período
+pub async fn professor_loads<C: ConnectionTrait>(
+    db: &C,
+    school_year: &str,
+    period: &str,
+    profesor_id: Option<i32>,
+) -> Result<Vec<ProfessorLoad>, DbErr> {
+    let mut query = profesores::Entity::find().order_by_asc(profesores::Column::Name);
+    if let Some(id) = profesor_id {
+        query = query.filter(profesores::Column::Id.eq(id));
+    }
+    let professors = query.all(db).await?;
+
+    let professor_ids: Vec<i32> = professors.iter().map(|p| p.id).collect();
+    let assignments = asignaciones_profesores::Entity::find()
+        .filter(asignaciones_profesores::Column::ProfesorId.is_in(professor_ids))
+        .all(db)
+        .await?;
+
+    let balances = balances::Entity::find()
+        .filter(balances::Column::SchoolYear.eq(school_year))
+        .filter(balances::Column::Period.eq(period))
+        .all(db)
+        .await?;
+    let balance_ids: Vec<i32> = balances.iter().map(|b| b.id).collect();
+    let career_ids: Vec<i32> = balances.iter().filter_map(|b| b.carrera_id).collect();
+    let subject_ids: Vec<i32> = assignments.iter().map(|a| a.asignatura_id).collect();
+    let allocations = asignaciones_horas::Entity::find()
+        .filter(asignaciones_horas::Column::BalanceId.is_in(balance_ids.clone()))
+        .filter(asignaciones_horas::Column::AsignaturaId.is_in(subject_ids))
+        .all(db)
+        .await?;
+    let own_groups = grupos::Entity::find()
+        .filter(grupos::Column::BalanceId.is_in(balance_ids))
+        .all(db)
+        .await?;
+    let year_groups = grupos_anio::Entity::find()
+        .filter(grupos_anio::Column::CarreraId.is_in(career_ids))
+        .all(db)
+        .await?;
+    let groups = balances
+        .iter()
+        .map(|b| (b.id, effective_groups(b, &own_groups, &year_groups)))
+        .collect();
+
+    Ok(compute_loads(&professors, &assignments, &allocations, &groups))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::totals;
+
+    fn professor(id: i32, max_weekly_hours: i32) -> profesores::Model {
+        profesores::Model {
+            id,
+            name: format!("Profesor {}", id),
+            email: None,
+            usuario_id: None,
+            max_weekly_hours,
+            created_at: None,
+        }
+    }
+
+    fn assignment(asignatura_id: i32, activity: &str, group_name: &str) -> asignaciones_profesores::Model {
+        asignaciones_profesores::Model {
+            id: 0,
+            profesor_id: 1,
+            asignatura_id,
+            activity: activity.to_string(),
+            group_name: group_name.to_string(),
+        }
+    }
+
+    fn allocation(balance_id: i32, week: i32, hours: TypeHours) -> asignaciones_horas::Model {
+        asignaciones_horas::Model {
+            balance_id,
+            ..totals::tests::allocation(1, week, hours)
+        }
+    }
+
+    fn group(name: &str, subgroups: i32) -> GroupSnapshot {
+        GroupSnapshot {
+            name: name.to_string(),
+            subgroups,
+        }
+    }
+
+    #[test]
+    fn lectures_count_once_and_lab_practice_per_subgroup() {
+        let assignments = [
+            assignment(1, "C", "A"),
+            assignment(1, "C", "B"),
+            assignment(1, "CP", "A"),
+            assignment(1, "CP", "B"),
+            assignment(1, "PL", "A"),
+        ];
+        let allocations = [allocation(1, 1, TypeHours { c: 2, cp: 2, pl: 2, ..Default::default() })];
+        let groups = HashMap::from([(1, vec![group("A", 3), group("B", 1)])]);
+
+        let loads = compute_loads(&[professor(1, 10)], &assignments, &allocations, &groups);
+
+        let week = &loads[0].weeks[0];
+        assert_eq!((week.hours.c, week.hours.cp, week.hours.pl), (2, 4, 6));
+        assert_eq!(week.total, 12);
+        assert!(week.overloaded);
+        assert_eq!(loads[0].overloaded_weeks, [1]);
+        assert_eq!(loads[0].total, 12);
+    }
+
+    #[test]
+    fn the_same_subject_in_two_balances_counts_once() {
+        let assignments = [assignment(1, "CP", "A")];
+        let allocations = [
+            allocation(1, 1, TypeHours { cp: 2, ..Default::default() }),
+            allocation(2, 1, TypeHours { cp: 4, ..Default::default() }),
+            allocation(2, 2, TypeHours { cp: 2, ..Default::default() }),
+        ];
+
+        let loads = compute_loads(&[professor(1, 16)], &assignments, &allocations, &HashMap::new());
+
+        assert_eq!(loads[0].weeks[0].hours.cp, 4);
+        assert_eq!(loads[0].weeks[1].hours.cp, 2);
+        assert_eq!(loads[0].total, 6);
+    }
+}